@@ -1,7 +1,6 @@
 use hyprlib::{Hyprland, Listener};
 use tokio::signal;
-use tokio::time::sleep;
-use tracing_subscriber::{fmt::format, prelude::*, FmtSubscriber};
+use tracing_subscriber::{fmt::format, FmtSubscriber};
 
 #[tokio::main]
 async fn main() {
@@ -22,6 +21,6 @@ async fn main() {
   println!("Received Ctrl-C event, shutting down...");
 
   if let Ok(socket) = socket {
-    socket.await.unwrap();
+    socket.await;
   }
 }
@@ -0,0 +1,167 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use hyprlib::events::{Event, EventKind, EventRef};
+use hyprlib::lines::LineReader;
+use tokio::io::AsyncBufReadExt;
+use tokio::runtime::Runtime;
+
+/// Path to a file of recorded events, one per line, e.g. captured with
+/// `socat -U - UNIX-CONNECT:$XDG_RUNTIME_DIR/hypr/$HYPRLAND_INSTANCE_SIGNATURE/.socket2.sock`.
+/// When unset the corpus is generated.
+const CORPUS_VAR: &str = "HYPRLIB_BENCH_CORPUS";
+
+/// Number of lines in the generated corpus.
+const CORPUS_LINES: usize = 1_000_000;
+
+/// A burst of events as Hyprland sends them while switching workspaces and opening a window.
+const LINES: &[&str] = &[
+  "workspace>>2",
+  "workspacev2>>2,2",
+  "focusedmon>>DP-1,2",
+  "activewindow>>kitty,~/src/hyprstream: cargo bench",
+  "activewindowv2>>55d5a8b2c9a0",
+  "openwindow>>55d5a8b2c9a0,2,firefox,Mozilla Firefox — Private Browsing",
+  "windowtitlev2>>55d5a8b2c9a0,Rust Documentation, Standard Library — Mozilla Firefox",
+  "movewindowv2>>55d5a8b2c9a0,3,3",
+  "togglegroup>>1,55d5a8b2c9a0,55d5a8b2d1f0,55d5a8b2e4c0",
+  "activespecialv2>>-98,special:scratch,DP-1",
+  "monitoraddedv2>>1,HDMI-A-1,Dell Inc. DELL U2720Q 8XYZ123",
+  "screencast>>1,0",
+];
+
+fn split(line: &str) -> (&str, &str) {
+  line.split_once(">>").unwrap()
+}
+
+fn parse(c: &mut Criterion) {
+  let mut group = c.benchmark_group("parse");
+  group.throughput(Throughput::Elements(LINES.len() as u64));
+
+  group.bench_function("owned", |b| {
+    b.iter(|| {
+      for line in LINES {
+        let (name, data) = split(line);
+        black_box(Event::parse(name, data));
+      }
+    })
+  });
+
+  group.bench_function("borrowed", |b| {
+    b.iter(|| {
+      for line in LINES {
+        black_box(EventRef::from_line(line));
+      }
+    })
+  });
+
+  group.bench_function("borrowed_to_owned", |b| {
+    b.iter(|| {
+      for line in LINES {
+        black_box(EventRef::from_line(line).map(EventRef::to_owned));
+      }
+    })
+  });
+
+  let names: Vec<&str> = LINES.iter().map(|line| split(line).0).collect();
+  group.bench_function("kind_only", |b| {
+    b.iter(|| {
+      for name in &names {
+        black_box(EventKind::from_name(black_box(name)));
+      }
+    })
+  });
+
+  group.finish();
+}
+
+/// The recorded corpus if one is given, otherwise [`CORPUS_LINES`] lines made from [`LINES`] with
+/// varying addresses and workspaces, so that runs are comparable across machines.
+fn corpus() -> String {
+  if let Ok(path) = std::env::var(CORPUS_VAR) {
+    return std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+  }
+
+  let mut corpus = String::with_capacity(CORPUS_LINES * 48);
+  let mut state = 0x2545f4914f6cdd1du64;
+  for i in 0..CORPUS_LINES {
+    // xorshift, to vary the numbers without depending on a random number generator.
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    let line = LINES[i % LINES.len()]
+      .replace("55d5a8b2c9a0", &format!("{:x}", state & 0xffff_ffff_ffff))
+      .replace(">>2", &format!(">>{}", state % 10 + 1));
+    corpus.push_str(&line);
+    corpus.push('\n');
+  }
+  corpus
+}
+
+fn corpus_group(c: &mut Criterion) {
+  let corpus = corpus();
+  let lines = corpus.lines().count() as u64;
+  let runtime = Runtime::new().unwrap();
+
+  let mut group = c.benchmark_group("corpus");
+  group.sample_size(10);
+  group.throughput(Throughput::Elements(lines));
+
+  group.bench_function("buf_reader_lines", |b| {
+    b.iter(|| {
+      runtime.block_on(async {
+        let mut lines = tokio::io::BufReader::new(corpus.as_bytes()).lines();
+        while let Some(line) = lines.next_line().await.unwrap() {
+          black_box(line);
+        }
+      })
+    })
+  });
+
+  group.bench_function("line_reader", |b| {
+    b.iter(|| {
+      runtime.block_on(async {
+        let mut lines = LineReader::new(corpus.as_bytes());
+        while let Some(line) = lines.next_line().await.unwrap() {
+          black_box(line);
+        }
+      })
+    })
+  });
+
+  group.bench_function("line_reader_kind", |b| {
+    b.iter(|| {
+      runtime.block_on(async {
+        let mut lines = LineReader::new(corpus.as_bytes());
+        while let Some(line) = lines.next_line().await.unwrap() {
+          black_box(EventKind::from_line(line));
+        }
+      })
+    })
+  });
+
+  group.bench_function("line_reader_borrowed", |b| {
+    b.iter(|| {
+      runtime.block_on(async {
+        let mut lines = LineReader::new(corpus.as_bytes());
+        while let Some(line) = lines.next_line().await.unwrap() {
+          black_box(EventRef::from_line(line));
+        }
+      })
+    })
+  });
+
+  group.bench_function("line_reader_owned", |b| {
+    b.iter(|| {
+      runtime.block_on(async {
+        let mut lines = LineReader::new(corpus.as_bytes());
+        while let Some(line) = lines.next_line().await.unwrap() {
+          black_box(EventRef::from_line(line).map(EventRef::to_owned));
+        }
+      })
+    })
+  });
+
+  group.finish();
+}
+
+criterion_group!(benches, parse, corpus_group);
+criterion_main!(benches);
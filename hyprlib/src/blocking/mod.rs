@@ -0,0 +1,292 @@
+//! Blocking versions of the [`Dispatcher`] and the [`Listener`], on
+//! [`std::os::unix::net::UnixStream`].
+//!
+//! They suit short lived programs such as keybind helpers, which run a query, dispatch and exit
+//! and shouldn't pay for starting an async runtime. Enable the `blocking` feature, and turn off the
+//! default `async-tokio` one to leave tokio out entirely. Events and replies are parsed by the same
+//! code as in the async versions.
+//!
+//! ```no_run
+//! use hyprlib::blocking::Dispatcher;
+//! use hyprlib::{Dispatch, Hyprland};
+//!
+//! # fn run() -> Result<(), hyprlib::DispatchError> {
+//! let dispatcher = Dispatcher::new(Hyprland::default());
+//! if let Some(window) = dispatcher.active_window()? {
+//!   dispatcher.dispatch(Dispatch::Event(format!("focused:{}", window.class)))?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+
+use hyprstream_proto::interpreter::Interpreter;
+use serde::de::DeserializeOwned;
+use tracing::{debug, error, info, warn};
+
+use crate::dispatch::check_dispatch_reply;
+use crate::envelope::{Envelope, ReceivedAt};
+use crate::events::{EventKind, EventKinds, Fullscreen};
+use crate::lines::LineReader;
+use crate::query::{
+  decode_active_window, fullscreen_statuses, json_request, with_mode, Client, Devices,
+  FullscreenStatus, Monitor, Workspace,
+};
+use crate::state::{Queries, State};
+use crate::{Dispatch, DispatchError, Hyprland, Socket};
+
+/// Sends dispatchers and queries to Hyprland's request socket, blocking until it answers.
+pub struct Dispatcher {
+  pub hyprland: Hyprland,
+}
+
+impl Dispatcher {
+  pub fn new(hyprland: Hyprland) -> Self {
+    Dispatcher { hyprland }
+  }
+
+  pub fn dispatch(&self, dispatch: Dispatch) -> Result<(), DispatchError> {
+    let reply = self.request(&format!("dispatch {}", dispatch.command()))?;
+    check_dispatch_reply(reply)
+  }
+
+  /// Runs a JSON query such as `clients` and decodes the reply.
+  pub fn query<T: DeserializeOwned>(&self, query: &str) -> Result<T, DispatchError> {
+    let reply = self.request(&json_request(query))?;
+    Ok(serde_json::from_str(&reply)?)
+  }
+
+  pub fn clients(&self) -> Result<Vec<Client>, DispatchError> {
+    self.query("clients")
+  }
+
+  pub fn monitors(&self) -> Result<Vec<Monitor>, DispatchError> {
+    self.query("monitors")
+  }
+
+  pub fn workspaces(&self) -> Result<Vec<Workspace>, DispatchError> {
+    self.query("workspaces")
+  }
+
+  pub fn devices(&self) -> Result<Devices, DispatchError> {
+    self.query("devices")
+  }
+
+  /// The focused window, `None` when nothing is focused.
+  pub fn active_window(&self) -> Result<Option<Client>, DispatchError> {
+    decode_active_window(self.query("activewindow")?)
+  }
+
+  /// Every window that is maximized or fullscreen, including fake fullscreen.
+  pub fn fullscreen_windows(&self) -> Result<Vec<FullscreenStatus>, DispatchError> {
+    Ok(fullscreen_statuses(&self.clients()?))
+  }
+
+  /// Runs the queries a [`State`] is built from.
+  pub fn state(&self) -> Result<State, DispatchError> {
+    let queries = Queries {
+      clients: self.clients()?,
+      workspaces: self.workspaces()?,
+      monitors: self.monitors()?,
+      devices: self.devices()?,
+      active_window: self.active_window()?,
+    };
+    Ok(State::from_queries(&queries))
+  }
+
+  /// Looks up the window a [`Fullscreen`] event is about, see
+  /// the async `Dispatcher::resolve_fullscreen`.
+  pub fn resolve_fullscreen(
+    &self,
+    event: &Fullscreen,
+  ) -> Result<Option<FullscreenStatus>, DispatchError> {
+    if !event.enter_fullscreen {
+      return Ok(None);
+    }
+    Ok(
+      self
+        .active_window()?
+        .map(|client| client.fullscreen_status()),
+    )
+  }
+
+  /// Fills in the mode of a [`Fullscreen`] event from the focused window, see
+  /// the async `Dispatcher::resolve_fullscreen_mode`.
+  pub fn resolve_fullscreen_mode(&self, event: Fullscreen) -> Result<Fullscreen, DispatchError> {
+    let status = self.resolve_fullscreen(&event)?;
+    Ok(with_mode(event, status))
+  }
+
+  /// Writes a single request to the request socket and returns the full reply. Hyprland closes
+  /// the connection once it has answered.
+  fn request(&self, request: &str) -> io::Result<String> {
+    let socket_path = self.hyprland.socket_path(Socket::Dispatcher);
+    let mut stream = UnixStream::connect(socket_path)?;
+    stream.write_all(request.as_bytes())?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply)
+  }
+}
+
+/// Reads Hyprland's event socket as an iterator of [`Envelope`]s.
+///
+/// Each call to `next` blocks until an event arrives. Sequence numbers and the handling of lines
+/// that can't be interpreted are the same as for the async listener: such lines are logged and
+/// skipped, and still consume a sequence number. An I/O error is yielded as `Err`; iteration ends
+/// when Hyprland closes the socket.
+///
+/// ```no_run
+/// use hyprlib::blocking::Listener;
+/// use hyprlib::events::{Event, EventKind};
+/// use hyprlib::Hyprland;
+///
+/// # fn run() -> std::io::Result<()> {
+/// for envelope in Listener::connect(&Hyprland::default())?.only(EventKind::Submap) {
+///   if let Event::Submap(submap) = envelope?.event {
+///     println!("{}", submap.submap_name);
+///   }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Listener<R = UnixStream> {
+  lines: LineReader<R>,
+  seq: u64,
+  lost: u64,
+  kinds: EventKinds,
+}
+
+impl Listener {
+  /// Connects to the event socket of `hyprland`.
+  pub fn connect(hyprland: &Hyprland) -> io::Result<Self> {
+    let stream = UnixStream::connect(hyprland.socket_path(Socket::Listener))?;
+    Ok(Listener::from_reader(stream))
+  }
+}
+
+impl<R: Read> Listener<R> {
+  /// Reads events from `reader` instead of the Hyprland socket, for example a recorded log.
+  pub fn from_reader(reader: R) -> Self {
+    Listener {
+      lines: LineReader::new(reader),
+      seq: 0,
+      lost: 0,
+      kinds: EventKinds::ALL,
+    }
+  }
+
+  /// Restricts the listener to the given kinds. Lines of other kinds are skipped without parsing
+  /// their data. Calling it again narrows the set further.
+  pub fn only(mut self, kinds: impl Into<EventKinds>) -> Self {
+    self.kinds = self.kinds & kinds;
+    self
+  }
+
+  /// Number of lines lost so far: lines that weren't valid UTF-8, and lines of a wanted kind whose
+  /// data couldn't be interpreted. Unlike jumps in [`Envelope::seq`], lines skipped by
+  /// [`only`](Listener::only) don't count.
+  pub fn lost(&self) -> u64 {
+    self.lost
+  }
+}
+
+impl<R: Read> Iterator for Listener<R> {
+  type Item = io::Result<Envelope>;
+
+  fn next(&mut self) -> Option<io::Result<Envelope>> {
+    loop {
+      let line = match self.lines.next_line_blocking() {
+        Ok(Some(line)) => line,
+        Ok(None) => return None,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+          self.seq += 1;
+          self.lost += 1;
+          error!(seq = self.seq, "Error: {}", e);
+          continue;
+        }
+        Err(e) => return Some(Err(e)),
+      };
+      let received_at = ReceivedAt::now();
+      self.seq += 1;
+      let seq = self.seq;
+      let interpreter = Interpreter::new(line);
+      let kind = interpreter.kind();
+      if !crate::wants_kind(self.kinds, kind) {
+        debug!(seq = seq, kind = ?kind, "Event skipped");
+        continue;
+      }
+
+      if kind == Some(EventKind::Unknown) {
+        warn!(seq = seq, line = line, "Unhandled event type");
+      }
+      match interpreter.interpret() {
+        Ok(event) => {
+          info!(seq = seq, event = ?event, "Event received");
+          return Some(Ok(Envelope {
+            seq,
+            received_at,
+            raw: line.to_string(),
+            event,
+          }));
+        }
+        Err(e) => {
+          self.lost += 1;
+          error!(seq = seq, "Error: {}", e);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dispatch::fake;
+  use crate::events::{Event, EventKinds, Submap};
+
+  #[test]
+  fn dispatch_and_query() {
+    let (hyprland, requests) = fake::serve(|request| match request {
+      "j/activewindow" => "{}".to_string(),
+      "j/monitors" => r#"[{"id": 0, "name": "DP-1"}]"#.to_string(),
+      "dispatch event bad" => "invalid dispatcher".to_string(),
+      _ => "ok".to_string(),
+    });
+    let dispatcher = Dispatcher::new(hyprland);
+
+    assert_eq!(dispatcher.active_window().unwrap(), None);
+    assert_eq!(dispatcher.monitors().unwrap()[0].name, "DP-1");
+    dispatcher
+      .dispatch(Dispatch::Event("toggle-bar".to_string()))
+      .unwrap();
+    assert!(matches!(
+      dispatcher.dispatch(Dispatch::Event("bad".to_string())),
+      Err(DispatchError::Rejected(_))
+    ));
+    assert_eq!(requests.lock().unwrap()[2], "dispatch event toggle-bar");
+  }
+
+  #[test]
+  fn listener_iterates_envelopes() {
+    let input = b"submap>>resize\nnot an event\nopenwindow>>garbage\nworkspace>>2\nsubmap>>\n";
+    let mut listener =
+      Listener::from_reader(&input[..]).only(EventKinds::WORKSPACE | EventKind::Submap);
+    let envelopes: Vec<Envelope> = listener.by_ref().collect::<io::Result<_>>().unwrap();
+    // Both bad lines were skipped for their kind, not lost.
+    assert_eq!(listener.lost(), 0);
+
+    let seqs: Vec<u64> = envelopes.iter().map(|envelope| envelope.seq).collect();
+    assert_eq!(seqs, [1, 4, 5]);
+    assert_eq!(envelopes[0].raw, "submap>>resize");
+    assert_eq!(
+      envelopes[2].event,
+      Event::Submap(Submap {
+        submap_name: String::new()
+      })
+    );
+  }
+}
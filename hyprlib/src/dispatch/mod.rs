@@ -0,0 +1,276 @@
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io;
+#[cfg(feature = "async")]
+use std::task::Poll;
+#[cfg(feature = "async")]
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+#[cfg(feature = "async")]
+use crate::events::Event;
+#[cfg(feature = "async")]
+use crate::{runtime, Dispatch, Hyprland, Listener, Socket};
+
+#[derive(Error, Debug)]
+pub enum DispatchError {
+  #[error("Failed to reach Hyprland: {0}")]
+  Io(#[from] io::Error),
+  #[error("Hyprland rejected the request: {0}")]
+  Rejected(String),
+  #[error("Failed to decode the reply: {0}")]
+  Decode(#[from] serde_json::Error),
+  #[error(transparent)]
+  Wait(#[from] WaitError),
+}
+
+/// Why waiting for an event gave up.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitError {
+  #[error("No matching event arrived in time")]
+  Timeout,
+  #[error("The listener stopped before a matching event arrived")]
+  Closed,
+}
+
+/// Hyprland answers `ok` to a dispatcher it ran and an error message otherwise.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn check_dispatch_reply(reply: String) -> Result<(), DispatchError> {
+  match reply.trim() {
+    "ok" => Ok(()),
+    _ => Err(DispatchError::Rejected(reply)),
+  }
+}
+
+/// Runs `future`, giving up with [`WaitError::Timeout`] once `deadline` has passed.
+#[cfg(feature = "async")]
+pub(crate) async fn until<T>(
+  deadline: Instant,
+  future: impl Future<Output = Result<T, DispatchError>>,
+) -> Result<T, DispatchError> {
+  let mut future = std::pin::pin!(future);
+  let mut timer = runtime::Timer::at(deadline);
+  std::future::poll_fn(|cx| match future.as_mut().poll(cx) {
+    Poll::Ready(result) => Poll::Ready(result),
+    Poll::Pending => timer
+      .poll_expired(cx)
+      .map(|()| Err(WaitError::Timeout.into())),
+  })
+  .await
+}
+
+#[cfg(feature = "async")]
+#[derive(Clone)]
+pub struct Dispatcher {
+  pub hyprland: Hyprland,
+}
+
+#[cfg(feature = "async")]
+impl Dispatcher {
+  pub fn new(hyprland: Hyprland) -> Self {
+    Dispatcher { hyprland }
+  }
+
+  pub async fn dispatch(&self, dispatch: Dispatch) -> Result<(), DispatchError> {
+    let reply = self
+      .request(&format!("dispatch {}", dispatch.command()))
+      .await?;
+    check_dispatch_reply(reply)
+  }
+
+  /// Runs `dispatch` and waits for the first event `listener` reads that satisfies `predicate`,
+  /// such as the `openwindow` of a program started with [`Dispatch::Exec`]. The subscription is
+  /// made before the dispatch is sent, so an event following it immediately isn't missed; events
+  /// caused by something else can still match. `timeout` covers the dispatch and the wait.
+  ///
+  /// `predicate` runs on the listener task, like the ones given to
+  /// [`Subscription::filter`](crate::Subscription::filter).
+  ///
+  /// ```no_run
+  /// use std::time::Duration;
+  ///
+  /// use hyprlib::events::Event;
+  /// use hyprlib::{Dispatch, Dispatcher, Hyprland, Listener};
+  ///
+  /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+  /// let listener = Listener::new(Hyprland::default());
+  /// let dispatcher = Dispatcher::new(Hyprland::default());
+  /// listener.listen().await?;
+  ///
+  /// let opened = dispatcher
+  ///   .dispatch_and_wait(
+  ///     &listener,
+  ///     Dispatch::Exec("firefox".to_string()),
+  ///     |event| matches!(event, Event::OpenWindow(w) if w.window_class == "firefox"),
+  ///     Duration::from_secs(10),
+  ///   )
+  ///   .await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn dispatch_and_wait<F>(
+    &self,
+    listener: &Listener,
+    dispatch: Dispatch,
+    predicate: F,
+    timeout: Duration,
+  ) -> Result<Event, DispatchError>
+  where
+    F: Fn(&Event) -> bool + Send + Sync + 'static,
+  {
+    let deadline = Instant::now() + timeout;
+    let mut subscription = listener.subscribe().filter(predicate);
+    until(deadline, self.dispatch(dispatch)).await?;
+    Ok(subscription.recv_until(deadline).await?.event)
+  }
+
+  /// Writes a single request to the request socket and returns the full reply. Hyprland closes
+  /// the connection once it has answered.
+  pub(crate) async fn request(&self, request: &str) -> io::Result<String> {
+    let socket_path = self.hyprland.socket_path(Socket::Dispatcher);
+    runtime::request(&socket_path, request).await
+  }
+}
+
+/// A stand-in for Hyprland's request socket, shared by the tests of modules that talk to it. It
+/// runs on its own thread, so it serves async and blocking clients alike.
+#[cfg(all(test, any(feature = "async", feature = "blocking")))]
+pub(crate) mod fake {
+  use std::io::{Read, Write};
+  use std::os::unix::net::UnixListener;
+  use std::sync::{Arc, Mutex};
+
+  use crate::Hyprland;
+
+  /// Serves every request with `reply` and records it, for the rest of the test.
+  pub(crate) fn serve<F>(reply: F) -> (Hyprland, Arc<Mutex<Vec<String>>>)
+  where
+    F: Fn(&str) -> String + Send + 'static,
+  {
+    let instance_id = format!("hyprlib-test-{}-{}", std::process::id(), next_id());
+    let hyprland = Hyprland::new(instance_id.clone());
+    let socket_path = hyprland.socket_path(crate::Socket::Dispatcher);
+    std::fs::create_dir_all(format!("/tmp/hypr/{}", instance_id)).unwrap();
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(socket_path).unwrap();
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    std::thread::spawn(move || {
+      while let Ok((mut stream, _)) = listener.accept() {
+        let mut buf = vec![0; 8192];
+        let n = stream.read(&mut buf).unwrap();
+        let request = String::from_utf8_lossy(&buf[..n]).to_string();
+        let response = reply(&request);
+        recorded.lock().unwrap().push(request);
+        // The client may have given up waiting already.
+        let _ = stream.write_all(response.as_bytes());
+      }
+    });
+    (hyprland, requests)
+  }
+
+  fn next_id() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+  }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+  use super::*;
+  use crate::events::WindowAddress;
+  use crate::runtime::{block_on, sleep};
+  use futures_util::future::join;
+
+  #[test]
+  fn dispatch_event() {
+    block_on(async {
+      let (hyprland, requests) = fake::serve(|request| match request {
+        "dispatch event bad" => "invalid dispatcher".to_string(),
+        _ => "ok".to_string(),
+      });
+      let dispatcher = Dispatcher::new(hyprland);
+
+      dispatcher
+        .dispatch(Dispatch::Event("toggle-bar".to_string()))
+        .await
+        .unwrap();
+      assert!(matches!(
+        dispatcher
+          .dispatch(Dispatch::Event("bad".to_string()))
+          .await,
+        Err(DispatchError::Rejected(_))
+      ));
+      assert_eq!(requests.lock().unwrap()[0], "dispatch event toggle-bar");
+    })
+  }
+
+  #[test]
+  fn dispatch_and_wait_for_the_new_window() {
+    block_on(async {
+      let (hyprland, requests) = fake::serve(|_| "ok".to_string());
+      let listener = Listener::new(hyprland.clone());
+      let dispatcher = Dispatcher::new(hyprland);
+      let opened =
+        |event: &Event| matches!(event, Event::OpenWindow(w) if w.window_class == "kitty");
+
+      let (event, _) = join(
+        dispatcher.dispatch_and_wait(
+          &listener,
+          Dispatch::Exec("kitty".to_string()),
+          opened,
+          Duration::from_secs(5),
+        ),
+        async {
+          sleep(Duration::from_millis(10)).await;
+          let lines = b"openwindow>>1a,1,firefox,Docs\nopenwindow>>2b,1,kitty,~\n";
+          listener.listen_on(&lines[..]).await;
+        },
+      )
+      .await;
+      assert_eq!(event.unwrap().window_address(), Some(WindowAddress(0x2b)));
+      assert_eq!(requests.lock().unwrap()[0], "dispatch exec kitty");
+
+      let timed_out = dispatcher
+        .dispatch_and_wait(
+          &listener,
+          Dispatch::Workspace("2".to_string()),
+          |_| true,
+          Duration::from_millis(20),
+        )
+        .await;
+      assert!(matches!(
+        timed_out,
+        Err(DispatchError::Wait(WaitError::Timeout))
+      ));
+    })
+  }
+
+  #[test]
+  fn dispatch_and_wait_bounds_a_slow_dispatch() {
+    block_on(async {
+      let (hyprland, _) = fake::serve(|_| {
+        std::thread::sleep(Duration::from_millis(500));
+        "ok".to_string()
+      });
+      let listener = Listener::new(hyprland.clone());
+      let started = Instant::now();
+      let timed_out = Dispatcher::new(hyprland)
+        .dispatch_and_wait(
+          &listener,
+          Dispatch::Workspace("2".to_string()),
+          |_| true,
+          Duration::from_millis(20),
+        )
+        .await;
+      assert!(matches!(
+        timed_out,
+        Err(DispatchError::Wait(WaitError::Timeout))
+      ));
+      assert!(started.elapsed() < Duration::from_millis(400));
+    })
+  }
+}
@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::events::Event;
+
+/// `Envelope` wraps every [`Event`] handed out by a `Listener` together with the metadata recorded
+/// when its line was read from the socket. Stream adapters that turn events into something else
+/// keep the metadata by mapping the payload, see [`Envelope::map`].
+///
+/// # Fields
+///
+/// * `seq` - Position of the line in the listener's stream, starting at 1. Every line consumes a
+///   number, including lines that could not be interpreted and lines left out by `only` or
+///   `filter`. A jump between two envelopes only means lines were lost when nothing was left out,
+///   `Subscription::lost` tells otherwise.
+/// * `received_at` - When the line was read, as both a monotonic and a wall clock reading.
+/// * `raw` - The line exactly as Hyprland sent it, without the trailing newline.
+/// * `event` - The interpreted event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope<E = Event> {
+  pub seq: u64,
+  pub received_at: ReceivedAt,
+  pub raw: String,
+  pub event: E,
+}
+
+impl<E> Envelope<E> {
+  /// Replaces the payload while keeping the metadata.
+  pub fn map<F, T>(self, f: F) -> Envelope<T>
+  where
+    F: FnOnce(E) -> T,
+  {
+    Envelope {
+      seq: self.seq,
+      received_at: self.received_at,
+      raw: self.raw,
+      event: f(self.event),
+    }
+  }
+
+  /// Time spent between reading the line and now, useful to measure handler latency.
+  pub fn age(&self) -> Duration {
+    self.received_at.instant.elapsed()
+  }
+
+  /// Number of lines read between `previous` and this envelope, zero when they are adjacent. The
+  /// lines may have been lost or left out by a filter.
+  pub fn gap_since<T>(&self, previous: &Envelope<T>) -> u64 {
+    self.seq.saturating_sub(previous.seq + 1)
+  }
+}
+
+/// The moment a line was read from the socket.
+///
+/// # Fields
+///
+/// * `instant` - Monotonic clock reading, for measuring latency and intervals.
+/// * `system` - Wall clock reading, for logs and bug reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceivedAt {
+  pub instant: Instant,
+  pub system: SystemTime,
+}
+
+impl ReceivedAt {
+  pub fn now() -> Self {
+    ReceivedAt {
+      instant: Instant::now(),
+      system: SystemTime::now(),
+    }
+  }
+}
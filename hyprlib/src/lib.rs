@@ -1,11 +1,30 @@
-mod events;
-mod interpreter;
-
-use crate::interpreter::Interpreter;
-use tokio::net::UnixStream;
-
-use tokio::io::{self, AsyncBufReadExt, BufReader};
-use tracing::{error, event, info, span, Level};
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod dispatch;
+pub mod envelope;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub mod lines;
+#[cfg(feature = "async")]
+mod listener;
+pub mod query;
+#[cfg(feature = "async")]
+mod runtime;
+pub mod screencast;
+pub mod state;
+#[cfg(feature = "async")]
+pub mod stream;
+
+#[cfg(feature = "async")]
+pub use crate::dispatch::Dispatcher;
+pub use crate::dispatch::{DispatchError, WaitError};
+pub use crate::envelope::{Envelope, ReceivedAt};
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::events::{EventKind, EventKinds};
+#[cfg(feature = "async")]
+pub use crate::listener::{Listener, Subscription, WindowSelector};
+#[cfg(feature = "async")]
+pub use crate::runtime::Task;
+pub use hyprstream_proto::{custom, events, monitor, Dispatch};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Hyprland {
@@ -17,97 +36,46 @@ impl Hyprland {
     Hyprland { instance_id }
   }
 
-  pub fn default() -> Self {
+  #[cfg(any(feature = "async", feature = "blocking"))]
+  pub(crate) fn socket_path(&self, socket_type: Socket) -> String {
+    match socket_type {
+      Socket::Listener => format!("/tmp/hypr/{}/.socket2.sock", self.instance_id),
+      Socket::Dispatcher => format!("/tmp/hypr/{}/.socket.sock", self.instance_id),
+    }
+  }
+}
+
+impl Default for Hyprland {
+  fn default() -> Self {
     Hyprland {
       instance_id: std::env::var("HYPRLAND_INSTANCE_SIGNATURE").unwrap(),
     }
   }
 }
 
-enum Socket {
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) enum Socket {
   Listener,
   Dispatcher,
 }
 
-pub struct Dispatcher {
-  pub hyprland: Hyprland,
-}
-
-pub struct Listener {
-  pub hyprland: Hyprland,
-}
-
-impl Listener {
-  pub fn new(hyprland: Hyprland) -> Self {
-    Listener { hyprland }
-  }
-
-  pub async fn listen(&self) -> io::Result<tokio::task::JoinHandle<()>> {
-    let socket_path = self.get_socket_path(Socket::Listener);
-    let stream = UnixStream::connect(&socket_path).await?;
-
-    // Wrap the stream in a BufReader, which provides the lines method
-    let reader = BufReader::new(stream);
-
-    let mut num_lines = 0;
-
-    let listener_handle = tokio::spawn(async move {
-      let mut lines = reader.lines();
-      while let Ok(line) = lines.next_line().await {
-        num_lines += 1;
-        match line {
-          Some(line) => {
-            let event = Interpreter::new(line.as_str()).interpret();
-            match event {
-              Ok(event) => {
-                info!(num_lines = ?num_lines, event = ?event, "Event received");
-              }
-              Err(e) => {
-                error!("Error: {}", e);
-              }
-            }
-          }
-          None => {
-            error!("End of file...");
-            break;
-          }
-        }
-      }
-    });
-
-    Ok(listener_handle)
-  }
-
-  fn get_socket_path(&self, socket_type: Socket) -> String {
-    match socket_type {
-      Socket::Listener => format!("/tmp/hypr/{}/.socket2.sock", self.hyprland.instance_id),
-      Socket::Dispatcher => format!("/tmp/hypr/{}/.socket.sock", self.hyprland.instance_id),
-    }
+/// Whether a line of kind `kind` should be parsed for a consumer of `kinds`. Malformed lines only
+/// reach consumers that haven't narrowed their kinds.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn wants_kind(kinds: EventKinds, kind: Option<EventKind>) -> bool {
+  match kind {
+    Some(kind) => kinds.contains(kind),
+    None => kinds == EventKinds::ALL,
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use std::thread::sleep;
 
   #[test]
   fn init() {
     let hyprland = Hyprland::default();
-    assert!(hyprland.instance_id.len() > 0);
-  }
-}
-
-mod async_tests {
-  use super::*;
-  use tokio::time::{sleep, Duration};
-
-  #[tokio::test]
-  async fn async_listen() {
-    let hyprland = Hyprland::default();
-    let listener = Listener::new(hyprland);
-    listener.listen().await.unwrap();
-
-    sleep(Duration::from_secs(5)).await;
+    assert!(!hyprland.instance_id.is_empty());
   }
 }
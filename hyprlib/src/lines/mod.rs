@@ -0,0 +1,170 @@
+use std::io;
+use std::ops::Range;
+
+#[cfg(feature = "async")]
+use crate::runtime::{self, AsyncRead};
+
+/// Size of the buffer a [`LineReader`] starts with. Hyprland's lines are short, so it only grows
+/// for unusually long window titles.
+const INITIAL_CAPACITY: usize = 8 * 1024;
+
+/// Splits a byte stream into lines while reusing a single buffer.
+///
+/// Unlike `lines()` on a buffered reader, which allocates a `String` per line, `next_line` (async,
+/// with `async-tokio` or `async-smol`) and `next_line_blocking` (with the `blocking` feature) hand out
+/// a slice of the internal buffer that stays valid until the next call. Newlines are found with
+/// `memchr`.
+///
+/// Lines end with `\n` or `\r\n`, which are not part of the returned slice. A last line without
+/// a newline is returned when the stream ends.
+pub struct LineReader<R> {
+  reader: R,
+  buffer: Vec<u8>,
+  start: usize,
+  end: usize,
+}
+
+impl<R> LineReader<R> {
+  pub fn new(reader: R) -> Self {
+    LineReader {
+      reader,
+      buffer: vec![0; INITIAL_CAPACITY],
+      start: 0,
+      end: 0,
+    }
+  }
+
+  /// Finds the next complete line in the bytes already read and consumes it.
+  fn split_line(&mut self) -> Option<Range<usize>> {
+    let newline = memchr::memchr(b'\n', &self.buffer[self.start..self.end])?;
+    let line = self.start..self.start + newline;
+    self.start = line.end + 1;
+    Some(line)
+  }
+
+  /// Consumes whatever is left once the stream has ended.
+  fn split_rest(&mut self) -> Option<Range<usize>> {
+    if self.start == self.end {
+      return None;
+    }
+    let line = self.start..self.end;
+    self.start = self.end;
+    Some(line)
+  }
+
+  /// Moves the unconsumed bytes to the front, or grows the buffer when they already fill it.
+  fn make_room(&mut self) {
+    if self.start > 0 {
+      self.buffer.copy_within(self.start..self.end, 0);
+      self.end -= self.start;
+      self.start = 0;
+    }
+    if self.end == self.buffer.len() {
+      self.buffer.resize(self.buffer.len() * 2, 0);
+    }
+  }
+
+  fn line(&self, line: Range<usize>) -> io::Result<&str> {
+    let line = &self.buffer[line];
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    std::str::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + Unpin> LineReader<R> {
+  /// Reads the next line. `Ok(None)` once the stream has ended and every line was returned.
+  ///
+  /// A line that isn't valid UTF-8 gives an [`InvalidData`](io::ErrorKind::InvalidData) error;
+  /// it is consumed, so reading can go on with the next one.
+  pub async fn next_line(&mut self) -> io::Result<Option<&str>> {
+    loop {
+      if let Some(line) = self.split_line() {
+        return self.line(line).map(Some);
+      }
+      self.make_room();
+      let read = runtime::read(&mut self.reader, &mut self.buffer[self.end..]).await?;
+      if read == 0 {
+        return match self.split_rest() {
+          Some(line) => self.line(line).map(Some),
+          None => Ok(None),
+        };
+      }
+      self.end += read;
+    }
+  }
+}
+
+#[cfg(feature = "blocking")]
+impl<R: io::Read> LineReader<R> {
+  /// Reads the next line, blocking until it is complete. Otherwise the same as the async
+  /// `next_line`.
+  pub fn next_line_blocking(&mut self) -> io::Result<Option<&str>> {
+    loop {
+      if let Some(line) = self.split_line() {
+        return self.line(line).map(Some);
+      }
+      self.make_room();
+      let read = match self.reader.read(&mut self.buffer[self.end..]) {
+        Ok(read) => read,
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+        Err(e) => return Err(e),
+      };
+      if read == 0 {
+        return match self.split_rest() {
+          Some(line) => self.line(line).map(Some),
+          None => Ok(None),
+        };
+      }
+      self.end += read;
+    }
+  }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+  use super::*;
+  use crate::runtime::{block_on, Chunks};
+
+  async fn collect<R: AsyncRead + Unpin>(mut lines: LineReader<R>) -> Vec<String> {
+    let mut collected = Vec::new();
+    while let Some(line) = lines.next_line().await.unwrap() {
+      collected.push(line.to_string());
+    }
+    collected
+  }
+
+  #[test]
+  fn lines_across_reads() {
+    block_on(async {
+      let reader = Chunks::new(b"workspace>>2\r\n\nsubmap>>resize\nscreencast>>1,0", 4);
+      assert_eq!(
+        collect(LineReader::new(reader)).await,
+        ["workspace>>2", "", "submap>>resize", "screencast>>1,0"]
+      );
+    })
+  }
+
+  #[test]
+  fn lines_longer_than_the_buffer() {
+    block_on(async {
+      let title = "🦀".repeat(INITIAL_CAPACITY);
+      let input = format!("windowtitlev2>>1,{}\nworkspace>>2\n", title);
+      let lines = collect(LineReader::new(input.as_bytes())).await;
+      assert_eq!(lines.len(), 2);
+      assert_eq!(lines[0], format!("windowtitlev2>>1,{}", title));
+      assert_eq!(lines[1], "workspace>>2");
+    })
+  }
+
+  #[test]
+  fn invalid_utf8_is_skipped() {
+    block_on(async {
+      let mut lines = LineReader::new(&b"submap>>\xff\nsubmap>>resize\n"[..]);
+      let error = lines.next_line().await.unwrap_err();
+      assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+      assert_eq!(lines.next_line().await.unwrap(), Some("submap>>resize"));
+      assert_eq!(lines.next_line().await.unwrap(), None);
+    })
+  }
+}
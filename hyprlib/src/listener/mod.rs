@@ -0,0 +1,430 @@
+mod wait;
+
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+use hyprstream_proto::interpreter::Interpreter;
+use tracing::{debug, error, info, warn};
+
+use crate::envelope::{Envelope, ReceivedAt};
+use crate::events::{Event, EventKind, EventKinds};
+use crate::lines::LineReader;
+use crate::runtime::{self, AsyncRead, Task, Timer};
+use crate::{Hyprland, Socket, WaitError};
+
+pub use wait::WindowSelector;
+
+pub struct Listener {
+  pub hyprland: Hyprland,
+  seq: Arc<AtomicU64>,
+  handled: Arc<AtomicU64>,
+  subscribers: Arc<Mutex<Vec<Subscriber>>>,
+  /// How many lines were interpreted, to check that filtered lines are skipped.
+  #[cfg(test)]
+  interpreted: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+type Predicate = Box<dyn Fn(&Event) -> bool + Send + Sync>;
+
+/// What a [`Subscription`] wants to receive. Shared between the subscription, which edits it, and
+/// the listener task, which applies it to every line.
+struct Filter {
+  kinds: EventKinds,
+  predicates: Vec<Predicate>,
+}
+
+impl Filter {
+  /// Whether a line of the given kind should be parsed at all for this subscriber.
+  fn wants_kind(&self, kind: Option<EventKind>) -> bool {
+    crate::wants_kind(self.kinds, kind)
+  }
+
+  fn wants(&self, event: &Event) -> bool {
+    self.predicates.iter().all(|predicate| predicate(event))
+  }
+}
+
+struct Subscriber {
+  sender: mpsc::UnboundedSender<Envelope>,
+  filter: Arc<Mutex<Filter>>,
+  lost: Arc<AtomicU64>,
+  delivered: Arc<AtomicU64>,
+}
+
+/// How far a listener got with its lines, and what it handed to one subscription, for code that
+/// has to know whether the events the subscription will receive are all applied.
+///
+/// # Fields
+///
+/// * `read` - The sequence number of the last line read.
+/// * `handled` - The sequence number of the last line handed to every subscriber wanting it.
+/// * `delivered` - The sequence number of the last envelope sent to the subscription.
+#[derive(Debug, Clone)]
+pub(crate) struct Cursor {
+  read: Arc<AtomicU64>,
+  handled: Arc<AtomicU64>,
+  delivered: Arc<AtomicU64>,
+}
+
+impl Cursor {
+  /// The last line read, `None` while it is still being handed out.
+  pub(crate) fn settled(&self) -> Option<u64> {
+    let read = self.read.load(Ordering::SeqCst);
+    (self.handled.load(Ordering::SeqCst) == read).then_some(read)
+  }
+
+  pub(crate) fn delivered(&self) -> u64 {
+    self.delivered.load(Ordering::SeqCst)
+  }
+}
+
+/// A handle receiving every [`Envelope`] read by a [`Listener`] after the subscription was made.
+///
+/// By default every event is delivered. [`only`](Subscription::only) and
+/// [`filter`](Subscription::filter) narrow that down; lines whose kind no subscriber wants are
+/// skipped without parsing their data.
+///
+/// Lines left out that way still use up sequence numbers, so [`Envelope::seq`] jumps over them.
+/// [`lost`](Subscription::lost) counts only the lines the subscription would have received had
+/// they been readable.
+pub struct Subscription {
+  receiver: mpsc::UnboundedReceiver<Envelope>,
+  filter: Arc<Mutex<Filter>>,
+  lost: Arc<AtomicU64>,
+  cursor: Cursor,
+}
+
+impl Subscription {
+  /// Restricts the subscription to the given kinds. Calling it again narrows the set further.
+  ///
+  /// ```no_run
+  /// # use hyprlib::{Hyprland, Listener};
+  /// use hyprlib::events::EventKinds;
+  ///
+  /// let listener = Listener::new(Hyprland::default());
+  /// let bar = listener.subscribe().only(EventKinds::WINDOW | EventKinds::WORKSPACE);
+  /// ```
+  pub fn only(self, kinds: impl Into<EventKinds>) -> Self {
+    {
+      let mut filter = self.filter.lock().unwrap();
+      filter.kinds = filter.kinds & kinds;
+    }
+    self
+  }
+
+  /// Adds a predicate every delivered event has to satisfy. Predicates run on the listener task
+  /// after [`only`](Subscription::only) has been applied, so they should be cheap.
+  ///
+  /// ```no_run
+  /// # use hyprlib::{Hyprland, Listener};
+  /// use hyprlib::events::{Event, EventKind};
+  ///
+  /// let listener = Listener::new(Hyprland::default());
+  /// let firefox = listener
+  ///   .subscribe()
+  ///   .only(EventKind::OpenWindow)
+  ///   .filter(|event| matches!(event, Event::OpenWindow(w) if w.window_class == "firefox"));
+  /// ```
+  pub fn filter<F>(self, predicate: F) -> Self
+  where
+    F: Fn(&Event) -> bool + Send + Sync + 'static,
+  {
+    self
+      .filter
+      .lock()
+      .unwrap()
+      .predicates
+      .push(Box::new(predicate));
+    self
+  }
+
+  /// Number of lines lost since subscribing: lines that weren't valid UTF-8, and lines of a kind
+  /// this subscription wants whose data couldn't be interpreted. It is updated before the next
+  /// envelope is delivered, so a change seen when receiving one means lines before it were lost.
+  pub fn lost(&self) -> u64 {
+    self.lost.load(Ordering::Relaxed)
+  }
+
+  pub(crate) fn cursor(&self) -> Cursor {
+    self.cursor.clone()
+  }
+
+  /// Waits for the next envelope. Returns `None` once the listener has been dropped and its
+  /// reading task has finished.
+  pub async fn recv(&mut self) -> Option<Envelope> {
+    std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+  }
+
+  /// Waits for the next envelope for at most `timeout`.
+  pub async fn recv_timeout(&mut self, timeout: Duration) -> Result<Envelope, WaitError> {
+    self.recv_until(Instant::now() + timeout).await
+  }
+
+  pub(crate) async fn recv_until(&mut self, deadline: Instant) -> Result<Envelope, WaitError> {
+    let mut timer = Timer::at(deadline);
+    std::future::poll_fn(|cx| match Pin::new(&mut *self).poll_next(cx) {
+      Poll::Ready(Some(envelope)) => Poll::Ready(Ok(envelope)),
+      Poll::Ready(None) => Poll::Ready(Err(WaitError::Closed)),
+      Poll::Pending => timer.poll_expired(cx).map(|()| Err(WaitError::Timeout)),
+    })
+    .await
+  }
+}
+
+impl Stream for Subscription {
+  type Item = Envelope;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Envelope>> {
+    Pin::new(&mut self.receiver).poll_next(cx)
+  }
+}
+
+impl Listener {
+  pub fn new(hyprland: Hyprland) -> Self {
+    Listener {
+      hyprland,
+      seq: Arc::new(AtomicU64::new(0)),
+      handled: Arc::new(AtomicU64::new(0)),
+      subscribers: Arc::new(Mutex::new(Vec::new())),
+      #[cfg(test)]
+      interpreted: Default::default(),
+    }
+  }
+
+  pub fn subscribe(&self) -> Subscription {
+    let (sender, receiver) = mpsc::unbounded();
+    let filter = Arc::new(Mutex::new(Filter {
+      kinds: EventKinds::ALL,
+      predicates: Vec::new(),
+    }));
+    let lost = Arc::new(AtomicU64::new(0));
+    let cursor = Cursor {
+      read: self.seq.clone(),
+      handled: self.handled.clone(),
+      delivered: Arc::new(AtomicU64::new(0)),
+    };
+    self.subscribers.lock().unwrap().push(Subscriber {
+      sender,
+      filter: filter.clone(),
+      lost: lost.clone(),
+      delivered: cursor.delivered.clone(),
+    });
+    Subscription {
+      receiver,
+      filter,
+      lost,
+      cursor,
+    }
+  }
+
+  /// Connects to the event socket and spawns the task delivering its events to subscribers.
+  pub async fn listen(&self) -> io::Result<Task> {
+    let socket_path = self.hyprland.socket_path(Socket::Listener);
+    let stream = runtime::connect(&socket_path).await?;
+
+    Ok(self.listen_on(stream))
+  }
+
+  /// Reads events from `reader` instead of the Hyprland socket. Sequence numbers continue from
+  /// wherever previous calls on this listener stopped, so gaps stay visible across reconnects.
+  pub(crate) fn listen_on<R>(&self, reader: R) -> Task
+  where
+    R: AsyncRead + Unpin + Send + 'static,
+  {
+    let mut lines = LineReader::new(reader);
+    let seq = self.seq.clone();
+    let handled = self.handled.clone();
+    let subscribers = self.subscribers.clone();
+    #[cfg(test)]
+    let interpreted = self.interpreted.clone();
+
+    runtime::spawn(async move {
+      loop {
+        let line = match lines.next_line().await {
+          Ok(Some(line)) => line,
+          Ok(None) => {
+            error!("End of file...");
+            break;
+          }
+          Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+            let seq = seq.fetch_add(1, Ordering::SeqCst) + 1;
+            error!(seq = seq, "Error: {}", e);
+            for subscriber in subscribers.lock().unwrap().iter() {
+              subscriber.lost.fetch_add(1, Ordering::Relaxed);
+            }
+            handled.store(seq, Ordering::SeqCst);
+            continue;
+          }
+          Err(e) => {
+            error!("Error: {}", e);
+            break;
+          }
+        };
+        let received_at = ReceivedAt::now();
+        let seq = seq.fetch_add(1, Ordering::SeqCst) + 1;
+        let interpreter = Interpreter::new(line);
+        let kind = interpreter.kind();
+        let mut subscribers = subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+        if !subscribers
+          .iter()
+          .any(|subscriber| subscriber.filter.lock().unwrap().wants_kind(kind))
+        {
+          debug!(seq = seq, kind = ?kind, "Event skipped");
+          handled.store(seq, Ordering::SeqCst);
+          continue;
+        }
+
+        if kind == Some(EventKind::Unknown) {
+          warn!(seq = seq, line = line, "Unhandled event type");
+        }
+        #[cfg(test)]
+        interpreted.fetch_add(1, Ordering::Relaxed);
+        let event = interpreter.interpret();
+        match event {
+          Ok(event) => {
+            info!(seq = seq, event = ?event, "Event received");
+            let envelope = Envelope {
+              seq,
+              received_at,
+              raw: line.to_string(),
+              event,
+            };
+            for subscriber in subscribers.iter() {
+              let filter = subscriber.filter.lock().unwrap();
+              if filter.wants_kind(kind) && filter.wants(&envelope.event) {
+                subscriber.delivered.store(seq, Ordering::SeqCst);
+                let _ = subscriber.sender.unbounded_send(envelope.clone());
+              }
+            }
+          }
+          Err(e) => {
+            error!(seq = seq, "Error: {}", e);
+            for subscriber in subscribers.iter() {
+              if subscriber.filter.lock().unwrap().wants_kind(kind) {
+                subscriber.lost.fetch_add(1, Ordering::Relaxed);
+              }
+            }
+          }
+        }
+        handled.store(seq, Ordering::SeqCst);
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::events::Submap;
+  use crate::runtime::block_on;
+  use std::time::Duration;
+
+  #[test]
+  fn async_listen() {
+    block_on(async {
+      let hyprland = Hyprland::default();
+      let listener = Listener::new(hyprland);
+      let _task = listener.listen().await.unwrap();
+
+      runtime::sleep(Duration::from_secs(5)).await;
+    })
+  }
+
+  #[test]
+  fn envelopes_carry_seq_and_raw_line() {
+    block_on(async {
+      let listener = Listener::new(Hyprland::new("test".to_string()));
+      let mut subscription = listener.subscribe();
+      let input = b"submap>>resize\nnot an event\nsubmap>>\n";
+      listener.listen_on(&input[..]).await;
+
+      let first = subscription.recv().await.unwrap();
+      assert_eq!(first.seq, 1);
+      assert_eq!(first.raw, "submap>>resize");
+      assert_eq!(
+        first.event,
+        Event::Submap(Submap {
+          submap_name: "resize".to_string()
+        })
+      );
+
+      let second = subscription.recv().await.unwrap();
+      assert_eq!(second.seq, 3);
+      assert_eq!(second.gap_since(&first), 1);
+      assert!(second.received_at.instant >= first.received_at.instant);
+
+      drop(listener);
+      assert!(subscription.recv().await.is_none());
+    })
+  }
+
+  #[test]
+  fn subscriptions_only_receive_matching_events() {
+    block_on(async {
+      let listener = Listener::new(Hyprland::new("test".to_string()));
+      let mut everything = listener.subscribe();
+      let mut windows = listener
+        .subscribe()
+        .only(EventKinds::WINDOW)
+        .filter(|event| matches!(event, Event::OpenWindow(w) if w.window_class == "firefox"));
+      let input = b"workspace>>2\n\
+        openwindow>>1,2,kitty,~\n\
+        openwindow>>2,2,firefox,Mozilla Firefox\n";
+      listener.listen_on(&input[..]).await;
+      drop(listener);
+
+      let firefox = windows.recv().await.unwrap();
+      assert_eq!(firefox.seq, 3);
+      assert!(windows.recv().await.is_none());
+
+      let mut seqs = Vec::new();
+      while let Some(envelope) = everything.recv().await {
+        seqs.push(envelope.seq);
+      }
+      assert_eq!(seqs, vec![1, 2, 3]);
+    })
+  }
+
+  #[test]
+  fn only_lost_lines_count_as_lost() {
+    block_on(async {
+      let listener = Listener::new(Hyprland::new("test".to_string()));
+      let mut everything = listener.subscribe();
+      let mut submaps = listener.subscribe().only(EventKind::Submap);
+      let input = b"workspace>>2\nsubmap>>resize\nopenwindow>>garbage\n\xff\nsubmap>>\n";
+      listener.listen_on(&input[..]).await;
+      drop(listener);
+
+      let first = submaps.recv().await.unwrap();
+      let second = submaps.recv().await.unwrap();
+      assert_eq!((first.seq, second.seq), (2, 5));
+      // The filtered `workspace` and `openwindow` lines leave a gap that isn't a loss.
+      assert_eq!(second.gap_since(&first), 2);
+      assert_eq!(submaps.lost(), 1);
+
+      while everything.recv().await.is_some() {}
+      assert_eq!(everything.lost(), 2);
+    })
+  }
+
+  #[test]
+  fn unwanted_kinds_are_not_parsed() {
+    block_on(async {
+      let listener = Listener::new(Hyprland::new("test".to_string()));
+      let mut workspaces = listener.subscribe().only(EventKinds::WORKSPACE);
+      // This `movewindowv2` payload is invalid, but nobody wants it so it must never be parsed.
+      let input = b"movewindowv2>>garbage\nworkspace>>3\n";
+      listener.listen_on(&input[..]).await;
+
+      assert_eq!(workspaces.recv().await.unwrap().seq, 2);
+      assert_eq!(listener.interpreted.load(Ordering::Relaxed), 1);
+    })
+  }
+}
@@ -0,0 +1,307 @@
+use std::time::{Duration, Instant};
+
+use super::Listener;
+use crate::dispatch::until;
+use crate::events::{Event, WindowAddress};
+use crate::query::Client;
+use crate::{DispatchError, Dispatcher, WaitError};
+
+/// Picks the window [`Listener::wait_for_window`] waits for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowSelector {
+  /// A window of the given class, as set when it opened. Later class changes don't count.
+  Class(String),
+  /// A window with the given title, including one whose title changes to it.
+  Title(String),
+}
+
+impl WindowSelector {
+  fn matches_client(&self, client: &Client) -> bool {
+    match self {
+      WindowSelector::Class(class) => client.initial_class == *class,
+      WindowSelector::Title(title) => client.title == *title,
+    }
+  }
+
+  /// The window `event` makes match, if any.
+  fn matches_event(&self, event: &Event) -> Option<WindowAddress> {
+    match (self, event) {
+      (WindowSelector::Class(class), Event::OpenWindow(e)) if e.window_class == *class => {
+        Some(e.window_address)
+      }
+      (WindowSelector::Title(title), Event::OpenWindow(e)) if e.window_title == *title => {
+        Some(e.window_address)
+      }
+      (WindowSelector::Title(title), Event::WindowTitleV2(e)) if e.window_title == *title => {
+        Some(e.window_address)
+      }
+      _ => None,
+    }
+  }
+}
+
+impl Listener {
+  /// Waits for at most `timeout` for the first event satisfying `predicate`, which runs on the
+  /// listener task. Only events read after the call count; the typed shortcuts below, such as
+  /// [`wait_for_submap`](Listener::wait_for_submap), also look at what is already there.
+  ///
+  /// ```no_run
+  /// use std::time::Duration;
+  ///
+  /// use hyprlib::events::Event;
+  /// use hyprlib::{Hyprland, Listener};
+  ///
+  /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+  /// let listener = Listener::new(Hyprland::default());
+  /// listener.listen().await?;
+  /// listener
+  ///   .wait_for(
+  ///     |event| matches!(event, Event::Screencast(e) if e.state),
+  ///     Duration::from_secs(30),
+  ///   )
+  ///   .await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn wait_for<F>(&self, predicate: F, timeout: Duration) -> Result<Event, WaitError>
+  where
+    F: Fn(&Event) -> bool + Send + Sync + 'static,
+  {
+    let mut subscription = self.subscribe().filter(predicate);
+    Ok(subscription.recv_timeout(timeout).await?.event)
+  }
+
+  /// Waits for at most `timeout` for a window matching `selector` and returns its address. A
+  /// window that is already open counts, as found through the `clients` query of `dispatcher`,
+  /// which `timeout` covers as well.
+  pub async fn wait_for_window(
+    &self,
+    dispatcher: &Dispatcher,
+    selector: WindowSelector,
+    timeout: Duration,
+  ) -> Result<WindowAddress, DispatchError> {
+    let deadline = Instant::now() + timeout;
+    let matcher = selector.clone();
+    // Subscribing before the query, a window opening in between is seen either way.
+    let mut subscription = self
+      .subscribe()
+      .filter(move |event| matcher.matches_event(event).is_some());
+    let clients = until(deadline, dispatcher.clients()).await?;
+    if let Some(client) = clients
+      .iter()
+      .find(|client| selector.matches_client(client))
+    {
+      return Ok(client.address);
+    }
+
+    let envelope = subscription.recv_until(deadline).await?;
+    Ok(selector.matches_event(&envelope.event).unwrap())
+  }
+
+  /// Waits for at most `timeout` for the monitor named `name`, such as `DP-2`, to be connected. A
+  /// monitor that already is counts, as found through the `monitors` query of `dispatcher`, which
+  /// `timeout` covers as well.
+  pub async fn wait_for_monitor(
+    &self,
+    dispatcher: &Dispatcher,
+    name: &str,
+    timeout: Duration,
+  ) -> Result<(), DispatchError> {
+    let deadline = Instant::now() + timeout;
+    let wanted = name.to_string();
+    let mut subscription = self.subscribe().filter(move |event| match event {
+      Event::MonitorAdded(e) => e.monitor_name == wanted,
+      Event::MonitorAddedV2(e) => e.monitor_name == wanted,
+      _ => false,
+    });
+    let monitors = until(deadline, dispatcher.monitors()).await?;
+    if monitors.iter().any(|monitor| monitor.name == name) {
+      return Ok(());
+    }
+
+    subscription.recv_until(deadline).await?;
+    Ok(())
+  }
+
+  /// Waits for at most `timeout` for the keybind submap named `name` to be active, `""` for the
+  /// default one. A submap that already is counts, as found through the `submap` query of
+  /// `dispatcher`, which `timeout` covers as well. Releases rejecting that query only wait for the
+  /// `submap` event.
+  pub async fn wait_for_submap(
+    &self,
+    dispatcher: &Dispatcher,
+    name: &str,
+    timeout: Duration,
+  ) -> Result<(), DispatchError> {
+    let deadline = Instant::now() + timeout;
+    let wanted = name.to_string();
+    let mut subscription = self
+      .subscribe()
+      .filter(move |event| matches!(event, Event::Submap(e) if e.submap_name == wanted));
+    match until(deadline, dispatcher.submap()).await {
+      Ok(submap) if submap == name => return Ok(()),
+      Ok(_) | Err(DispatchError::Rejected(_)) => {}
+      Err(e) => return Err(e),
+    }
+
+    subscription.recv_until(deadline).await?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dispatch::fake;
+  use crate::runtime::{block_on, sleep};
+  use futures_util::future::join;
+
+  fn serve() -> (Listener, Dispatcher) {
+    let (hyprland, _) = fake::serve(|request| {
+      match request {
+        "j/clients" => r#"[{ "address": "0x1a", "workspace": { "id": 1, "name": "1" }, "class": "scratchpad", "initialClass": "kitty", "title": "~" }]"#,
+        "j/monitors" => r#"[{ "id": 0, "name": "eDP-1" }]"#,
+        "submap" => "resize",
+        _ => "[]",
+      }
+      .to_string()
+    });
+    (Listener::new(hyprland.clone()), Dispatcher::new(hyprland))
+  }
+
+  #[test]
+  fn windows_already_open_count() {
+    block_on(async {
+      let (listener, dispatcher) = serve();
+      let selector = WindowSelector::Class("kitty".to_string());
+      let address = listener
+        .wait_for_window(&dispatcher, selector, Duration::from_millis(20))
+        .await
+        .unwrap();
+      assert_eq!(address, WindowAddress(0x1a));
+
+      let missing = listener
+        .wait_for_window(
+          &dispatcher,
+          WindowSelector::Class("firefox".to_string()),
+          Duration::from_millis(20),
+        )
+        .await;
+      assert!(matches!(
+        missing,
+        Err(DispatchError::Wait(WaitError::Timeout))
+      ));
+    })
+  }
+
+  #[test]
+  fn waits_for_windows_and_monitors_to_appear() {
+    block_on(async {
+      let (listener, dispatcher) = serve();
+      let timeout = Duration::from_secs(5);
+      let waits = async {
+        let title = WindowSelector::Title("Docs".to_string());
+        let window = listener.wait_for_window(&dispatcher, title, timeout).await;
+        let monitor = listener
+          .wait_for_monitor(&dispatcher, "DP-2", timeout)
+          .await;
+        (window.unwrap(), monitor.unwrap())
+      };
+      let events = async {
+        sleep(Duration::from_millis(10)).await;
+        let lines = b"openwindow>>2b,1,firefox,New Tab\nwindowtitlev2>>2b,Docs\n";
+        listener.listen_on(&lines[..]).await;
+        sleep(Duration::from_millis(50)).await;
+        listener
+          .listen_on(&b"monitoraddedv2>>1,DP-2,Dell\n"[..])
+          .await;
+      };
+
+      let ((window, ()), ()) = join(waits, events).await;
+      assert_eq!(window, WindowAddress(0x2b));
+    })
+  }
+
+  #[test]
+  fn submaps_already_active_count() {
+    block_on(async {
+      let (listener, dispatcher) = serve();
+      let timeout = Duration::from_millis(20);
+      listener
+        .wait_for_submap(&dispatcher, "resize", timeout)
+        .await
+        .unwrap();
+
+      let events = async {
+        sleep(Duration::from_millis(10)).await;
+        listener.listen_on(&b"submap>>\n"[..]).await;
+      };
+      let (default, ()) = join(
+        listener.wait_for_submap(&dispatcher, "", Duration::from_secs(5)),
+        events,
+      )
+      .await;
+      default.unwrap();
+
+      let (hyprland, _) = fake::serve(|_| "unknown request".to_string());
+      let old = Dispatcher::new(hyprland);
+      let missing = listener.wait_for_submap(&old, "", timeout).await;
+      assert!(matches!(
+        missing,
+        Err(DispatchError::Wait(WaitError::Timeout))
+      ));
+    })
+  }
+
+  #[test]
+  fn the_timeout_covers_the_query() {
+    block_on(async {
+      let (hyprland, _) = fake::serve(|_| {
+        std::thread::sleep(Duration::from_millis(500));
+        "[]".to_string()
+      });
+      let listener = Listener::new(hyprland.clone());
+      let dispatcher = Dispatcher::new(hyprland);
+      let timeout = Duration::from_millis(20);
+      let started = Instant::now();
+
+      let selector = WindowSelector::Class("kitty".to_string());
+      let window = listener
+        .wait_for_window(&dispatcher, selector, timeout)
+        .await;
+      assert!(matches!(
+        window,
+        Err(DispatchError::Wait(WaitError::Timeout))
+      ));
+      let monitor = listener
+        .wait_for_monitor(&dispatcher, "DP-2", timeout)
+        .await;
+      assert!(matches!(
+        monitor,
+        Err(DispatchError::Wait(WaitError::Timeout))
+      ));
+      assert!(started.elapsed() < Duration::from_millis(400));
+    })
+  }
+
+  #[test]
+  fn wait_for_takes_the_first_match_or_times_out() {
+    block_on(async {
+      let (listener, _) = serve();
+      let wait = listener.wait_for(|_| true, Duration::from_millis(20)).await;
+      assert_eq!(wait.unwrap_err(), WaitError::Timeout);
+
+      let events = async {
+        listener.listen_on(&b"submap>>resize\nsubmap>>\n"[..]).await;
+      };
+      let default_submap =
+        |event: &Event| matches!(event, Event::Submap(e) if e.submap_name.is_empty());
+      let (submap, ()) = join(
+        listener.wait_for(default_submap, Duration::from_secs(5)),
+        events,
+      )
+      .await;
+      assert_eq!(submap.unwrap().name(), "submap");
+    })
+  }
+}
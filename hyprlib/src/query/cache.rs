@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+
+use super::{decode_active_window, json_request, Client, Devices, Monitor, Workspace};
+use crate::envelope::Envelope;
+use crate::events::{Event, EventKind, EventKinds};
+use crate::runtime::{self, Task};
+use crate::{DispatchError, Dispatcher, Listener, Subscription};
+
+/// Events changing the reply of `clients` and `activewindow`.
+const WINDOWS: EventKinds = EventKinds::WINDOW
+  .union(EventKinds::GROUP)
+  .union(EventKinds::of(&[
+    EventKind::MoveWorkspace,
+    EventKind::MoveWorkspaceV2,
+    EventKind::RenameWorkspace,
+    EventKind::MonitorRemoved,
+    EventKind::MonitorRemovedV2,
+  ]));
+
+/// Events changing the reply of `workspaces`, which also counts windows and names the last
+/// focused one.
+const WORKSPACES: EventKinds = EventKinds::of(&[
+  EventKind::CreateWorkspace,
+  EventKind::CreateWorkspaceV2,
+  EventKind::DestroyWorkspace,
+  EventKind::DestroyWorkspaceV2,
+  EventKind::MoveWorkspace,
+  EventKind::MoveWorkspaceV2,
+  EventKind::RenameWorkspace,
+  EventKind::OpenWindow,
+  EventKind::CloseWindow,
+  EventKind::MoveWindow,
+  EventKind::MoveWindowV2,
+  EventKind::ActiveWindowV2,
+  EventKind::WindowTitle,
+  EventKind::WindowTitleV2,
+  EventKind::Fullscreen,
+  EventKind::MonitorAdded,
+  EventKind::MonitorAddedV2,
+  EventKind::MonitorRemoved,
+  EventKind::MonitorRemovedV2,
+]);
+
+/// Events changing the reply of `monitors`, which names the active workspaces.
+const MONITORS: EventKinds = EventKinds::MONITOR.union(EventKinds::of(&[
+  EventKind::Workspace,
+  EventKind::WorkspaceV2,
+  EventKind::MoveWorkspace,
+  EventKind::MoveWorkspaceV2,
+  EventKind::RenameWorkspace,
+  EventKind::ActiveSpecial,
+  EventKind::ActiveSpecialV2,
+]));
+
+/// Events changing the reply of `devices`. Plugging a device in sends nothing, so its reply can
+/// go stale until a gap or an unknown event clears the cache.
+const DEVICES: EventKinds = EventKinds::of(&[EventKind::ActiveLayout]);
+
+/// Answers repeated queries from memory until an event invalidates them.
+///
+/// Every cached query is tied to the [`EventKinds`] that change its reply, see
+/// [`QueryCache::invalidated_by`]. `clients`, `activewindow`, `workspaces`, `monitors` and
+/// `devices` are cached out of the box and [`cache`](QueryCache::cache) adds others; any other
+/// query goes to the socket every time. A [lost](Subscription::lost) line, `configreloaded` or an
+/// event this crate has no model for clears the whole cache, and nothing is cached anymore once
+/// the listener is gone.
+///
+/// A reply can be stale for as long as the event changing it takes to be read, which is the
+/// same window a [`Listener`] consumer runs with anyway.
+///
+/// ```no_run
+/// use hyprlib::query::QueryCache;
+/// use hyprlib::{Dispatcher, Hyprland, Listener};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let listener = Listener::new(Hyprland::default());
+/// let (cache, _task) = QueryCache::connect(&listener, Dispatcher::new(Hyprland::default()));
+/// listener.listen().await?;
+///
+/// let mut events = listener.subscribe();
+/// while events.recv().await.is_some() {
+///   // Only the first call after a window event reaches Hyprland.
+///   let clients = cache.clients().await?;
+///   println!("{} windows", clients.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct QueryCache {
+  dispatcher: Dispatcher,
+  shared: Arc<Mutex<Shared>>,
+}
+
+struct Shared {
+  rules: HashMap<String, EventKinds>,
+  replies: HashMap<String, serde_json::Value>,
+  /// Bumped on every invalidation, so that a reply requested before one isn't stored after it.
+  epoch: u64,
+  following: bool,
+}
+
+impl QueryCache {
+  /// A cache in front of `dispatcher`. It only caches once it [follows](QueryCache::follow) a
+  /// subscription.
+  pub fn new(dispatcher: Dispatcher) -> Self {
+    let rules = [
+      ("clients", WINDOWS),
+      ("activewindow", WINDOWS),
+      ("workspaces", WORKSPACES),
+      ("monitors", MONITORS),
+      ("devices", DEVICES),
+    ];
+    QueryCache {
+      dispatcher,
+      shared: Arc::new(Mutex::new(Shared {
+        rules: rules
+          .into_iter()
+          .map(|(query, kinds)| (query.to_string(), kinds))
+          .collect(),
+        replies: HashMap::new(),
+        epoch: 0,
+        following: false,
+      })),
+    }
+  }
+
+  /// A cache in front of `dispatcher`, invalidated by the events `listener` reads.
+  pub fn connect(listener: &Listener, dispatcher: Dispatcher) -> (Self, Task) {
+    let cache = QueryCache::new(dispatcher);
+    let task = cache.follow(listener.subscribe());
+    (cache, task)
+  }
+
+  /// Caches `query` until an event of `kinds` arrives, replacing the kinds it was tied to.
+  pub fn cache(self, query: &str, kinds: impl Into<EventKinds>) -> Self {
+    let mut shared = self.shared.lock().unwrap();
+    shared.rules.insert(query.to_string(), kinds.into());
+    shared.replies.remove(query);
+    drop(shared);
+    self
+  }
+
+  /// The events invalidating `query`, `None` when it isn't cached.
+  pub fn invalidated_by(&self, query: &str) -> Option<EventKinds> {
+    self.shared.lock().unwrap().rules.get(query).copied()
+  }
+
+  /// Drops every cached reply.
+  pub fn invalidate_all(&self) {
+    let mut shared = self.shared.lock().unwrap();
+    shared.replies.clear();
+    shared.epoch += 1;
+  }
+
+  /// Spawns a task invalidating the cache from every envelope `subscription` receives. Caching
+  /// stops once the subscription ends.
+  pub fn follow(&self, mut subscription: Subscription) -> Task {
+    self.shared.lock().unwrap().following = true;
+    let cache = self.clone();
+    runtime::spawn(async move {
+      let mut lost = 0;
+      while let Some(envelope) = subscription.recv().await {
+        let now = subscription.lost();
+        let gap = now != lost;
+        lost = now;
+        cache.apply(&envelope, gap);
+      }
+      cache.invalidate_all();
+      cache.shared.lock().unwrap().following = false;
+    })
+  }
+
+  fn apply(&self, envelope: &Envelope, gap: bool) {
+    let kind = match &envelope.event {
+      Event::ConfigReloaded | Event::Unknown { .. } => None,
+      event => Some(event.kind()),
+    };
+    let Some(kind) = kind.filter(|_| !gap) else {
+      self.invalidate_all();
+      return;
+    };
+
+    let mut shared = self.shared.lock().unwrap();
+    let Shared { rules, replies, .. } = &mut *shared;
+    // Bumping the epoch even when nothing was cached keeps a reply in flight from being stored.
+    if rules.values().any(|kinds| kinds.contains(kind)) {
+      replies.retain(|query, _| !rules[query].contains(kind));
+      shared.epoch += 1;
+    }
+  }
+
+  /// Runs a JSON query such as `clients`, answering from memory when it is cached and no event
+  /// changed it since.
+  pub async fn query<T: DeserializeOwned>(&self, query: &str) -> Result<T, DispatchError> {
+    let epoch = {
+      let shared = self.shared.lock().unwrap();
+      if let Some(reply) = shared.replies.get(query) {
+        return Ok(T::deserialize(reply)?);
+      }
+      shared.epoch
+    };
+
+    let reply = self.dispatcher.request(&json_request(query)).await?;
+    let reply: serde_json::Value = serde_json::from_str(&reply)?;
+    let decoded = T::deserialize(&reply)?;
+
+    let mut shared = self.shared.lock().unwrap();
+    if shared.following && shared.epoch == epoch && shared.rules.contains_key(query) {
+      shared.replies.insert(query.to_string(), reply);
+    }
+    Ok(decoded)
+  }
+
+  pub async fn clients(&self) -> Result<Vec<Client>, DispatchError> {
+    self.query("clients").await
+  }
+
+  pub async fn monitors(&self) -> Result<Vec<Monitor>, DispatchError> {
+    self.query("monitors").await
+  }
+
+  pub async fn workspaces(&self) -> Result<Vec<Workspace>, DispatchError> {
+    self.query("workspaces").await
+  }
+
+  pub async fn devices(&self) -> Result<Devices, DispatchError> {
+    self.query("devices").await
+  }
+
+  /// The focused window, `None` when nothing is focused.
+  pub async fn active_window(&self) -> Result<Option<Client>, DispatchError> {
+    decode_active_window(self.query("activewindow").await?)
+  }
+
+  /// The dispatcher behind the cache, for dispatches and uncached requests.
+  pub fn dispatcher(&self) -> &Dispatcher {
+    &self.dispatcher
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dispatch::fake;
+  use crate::envelope::ReceivedAt;
+  use crate::runtime::block_on;
+
+  fn serve() -> (QueryCache, Listener, Arc<Mutex<Vec<String>>>) {
+    let (hyprland, requests) = fake::serve(|request| {
+      match request {
+        "j/clients" => r#"[{ "address": "0x1a", "workspace": { "id": 1, "name": "1" } }]"#,
+        "j/workspaces" => r#"[{ "id": 1, "name": "1", "monitor": "DP-1" }]"#,
+        _ => "[]",
+      }
+      .to_string()
+    });
+    let listener = Listener::new(hyprland.clone());
+    let cache = QueryCache::new(Dispatcher::new(hyprland));
+    (cache, listener, requests)
+  }
+
+  fn envelope(line: &str) -> Envelope {
+    let (name, data) = line.split_once(">>").unwrap();
+    Envelope {
+      seq: 1,
+      received_at: ReceivedAt::now(),
+      raw: line.to_string(),
+      event: Event::from(name, data),
+    }
+  }
+
+  #[test]
+  fn events_invalidate_the_queries_they_change() {
+    block_on(async {
+      let (cache, listener, requests) = serve();
+      let task = cache.follow(listener.subscribe());
+
+      cache.clients().await.unwrap();
+      cache.workspaces().await.unwrap();
+      cache.clients().await.unwrap();
+      cache.workspaces().await.unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 2);
+
+      cache.apply(&envelope("submap>>resize"), false);
+      cache.clients().await.unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 2);
+
+      cache.apply(&envelope("windowtitlev2>>1a,vim"), false);
+      assert_eq!(cache.clients().await.unwrap()[0].address.0, 0x1a);
+      cache.workspaces().await.unwrap();
+      assert_eq!(
+        *requests.lock().unwrap(),
+        ["j/clients", "j/workspaces", "j/clients", "j/workspaces"]
+      );
+
+      // Once the listener is gone every query goes to the socket.
+      drop(listener);
+      task.await;
+      cache.monitors().await.unwrap();
+      cache.monitors().await.unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 6);
+    })
+  }
+
+  #[test]
+  fn gaps_and_unknown_events_clear_everything() {
+    block_on(async {
+      let (cache, listener, requests) = serve();
+      let cache = cache.cache("layers", EventKinds::LAYER);
+      assert_eq!(cache.invalidated_by("layers"), Some(EventKinds::LAYER));
+      assert_eq!(cache.invalidated_by("version"), None);
+      let _task = cache.follow(listener.subscribe());
+
+      cache.query::<serde_json::Value>("layers").await.unwrap();
+      cache.query::<serde_json::Value>("version").await.unwrap();
+      cache.query::<serde_json::Value>("layers").await.unwrap();
+      cache.query::<serde_json::Value>("version").await.unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 3);
+
+      cache.apply(&envelope("configreloaded>>"), false);
+      cache.query::<serde_json::Value>("layers").await.unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 4);
+
+      cache.apply(&envelope("submap>>resize"), true);
+      cache.query::<serde_json::Value>("layers").await.unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 5);
+
+      cache.apply(&envelope("somethingnew>>1"), false);
+      cache.query::<serde_json::Value>("layers").await.unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 6);
+    })
+  }
+}
@@ -0,0 +1,251 @@
+//! The types query replies decode into, re-exported from `hyprstream-proto`, and the
+//! `Dispatcher` methods running the queries.
+
+#[cfg(feature = "async")]
+mod cache;
+
+#[cfg(feature = "async")]
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "async")]
+pub use cache::QueryCache;
+pub use hyprstream_proto::query::*;
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::events::Fullscreen;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::events::FullscreenMode;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::DispatchError;
+#[cfg(feature = "async")]
+use crate::Dispatcher;
+
+/// The request for a query, prefixed so that Hyprland answers in JSON.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn json_request(query: &str) -> String {
+  format!("j/{}", query)
+}
+
+/// `activewindow` answers an empty object when nothing is focused.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn decode_active_window(
+  reply: serde_json::Value,
+) -> Result<Option<Client>, DispatchError> {
+  match reply.as_object() {
+    Some(object) if object.is_empty() => Ok(None),
+    _ => Ok(Some(serde_json::from_value(reply)?)),
+  }
+}
+
+/// Sets the mode of a `fullscreen` event from the status `resolve_fullscreen`
+/// found for it.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn with_mode(mut event: Fullscreen, status: Option<FullscreenStatus>) -> Fullscreen {
+  event.mode = match event.enter_fullscreen {
+    true => status.map(|status| status.mode),
+    false => Some(FullscreenMode::None),
+  };
+  event
+}
+
+/// The windows that are maximized or fullscreen, including fake fullscreen.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn fullscreen_statuses(clients: &[Client]) -> Vec<FullscreenStatus> {
+  clients
+    .iter()
+    .map(Client::fullscreen_status)
+    .filter(|status| status.mode != FullscreenMode::None || status.is_fake())
+    .collect()
+}
+
+#[cfg(feature = "async")]
+impl Dispatcher {
+  /// Runs a JSON query such as `clients` and decodes the reply.
+  pub async fn query<T: DeserializeOwned>(&self, query: &str) -> Result<T, DispatchError> {
+    let reply = self.request(&json_request(query)).await?;
+    Ok(serde_json::from_str(&reply)?)
+  }
+
+  pub async fn clients(&self) -> Result<Vec<Client>, DispatchError> {
+    self.query("clients").await
+  }
+
+  pub async fn monitors(&self) -> Result<Vec<Monitor>, DispatchError> {
+    self.query("monitors").await
+  }
+
+  pub async fn workspaces(&self) -> Result<Vec<Workspace>, DispatchError> {
+    self.query("workspaces").await
+  }
+
+  pub async fn devices(&self) -> Result<Devices, DispatchError> {
+    self.query("devices").await
+  }
+
+  /// The active keybind submap, empty for the default one. Releases before the `submap` query
+  /// existed reject it.
+  pub async fn submap(&self) -> Result<String, DispatchError> {
+    let reply = self.request("submap").await?;
+    match reply.trim() {
+      "default" => Ok(String::new()),
+      "unknown request" => Err(DispatchError::Rejected(reply)),
+      submap => Ok(submap.to_string()),
+    }
+  }
+
+  /// The focused window, `None` when nothing is focused.
+  pub async fn active_window(&self) -> Result<Option<Client>, DispatchError> {
+    decode_active_window(self.query("activewindow").await?)
+  }
+
+  /// Every window that is maximized or fullscreen, including fake fullscreen.
+  pub async fn fullscreen_windows(&self) -> Result<Vec<FullscreenStatus>, DispatchError> {
+    Ok(fullscreen_statuses(&self.clients().await?))
+  }
+
+  /// Looks up the window a [`Fullscreen`] event is about, which is the focused one, and the mode
+  /// it entered. Returns `None` when the event reports leaving fullscreen or nothing is focused
+  /// anymore.
+  pub async fn resolve_fullscreen(
+    &self,
+    event: &Fullscreen,
+  ) -> Result<Option<FullscreenStatus>, DispatchError> {
+    if !event.enter_fullscreen {
+      return Ok(None);
+    }
+    Ok(
+      self
+        .active_window()
+        .await?
+        .map(|client| client.fullscreen_status()),
+    )
+  }
+
+  /// Fills in the [`mode`](Fullscreen::mode) of a [`Fullscreen`] event, which Hyprland doesn't
+  /// send, from the focused window. Leaving fullscreen needs no query, and the mode stays `None`
+  /// when nothing is focused anymore.
+  pub async fn resolve_fullscreen_mode(
+    &self,
+    event: Fullscreen,
+  ) -> Result<Fullscreen, DispatchError> {
+    let status = self.resolve_fullscreen(&event).await?;
+    Ok(with_mode(event, status))
+  }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+  use super::*;
+  use crate::dispatch::fake;
+  use crate::runtime::block_on;
+
+  const CLIENT: &str = r#"{
+    "address": "0x55d5a8b2c9a0",
+    "mapped": true,
+    "hidden": false,
+    "at": [0, 0],
+    "size": [2560, 1440],
+    "workspace": { "id": 3, "name": "3" },
+    "floating": false,
+    "pseudo": false,
+    "monitor": 0,
+    "class": "mpv",
+    "title": "movie.mkv - mpv",
+    "initialClass": "mpv",
+    "initialTitle": "mpv",
+    "pid": 4242,
+    "xwayland": false,
+    "pinned": false,
+    "fullscreen": 1,
+    "fullscreenClient": 2,
+    "grouped": [],
+    "tags": [],
+    "swallowing": "0x0",
+    "focusHistoryID": 0
+  }"#;
+
+  #[test]
+  fn resolve_fullscreen_event() {
+    block_on(async {
+      let (hyprland, _) = fake::serve(|request| match request {
+        "j/activewindow" => CLIENT.to_string(),
+        _ => "unknown request".to_string(),
+      });
+      let dispatcher = Dispatcher::new(hyprland);
+
+      let entered = Fullscreen {
+        enter_fullscreen: true,
+        mode: None,
+      };
+      let status = dispatcher
+        .resolve_fullscreen(&entered)
+        .await
+        .unwrap()
+        .unwrap();
+      assert_eq!(status.workspace_id, 3);
+      assert_eq!(status.mode, FullscreenMode::Maximized);
+
+      let exited = Fullscreen {
+        enter_fullscreen: false,
+        mode: None,
+      };
+      assert_eq!(dispatcher.resolve_fullscreen(&exited).await.unwrap(), None);
+
+      let entered = dispatcher.resolve_fullscreen_mode(entered).await.unwrap();
+      assert_eq!(entered.mode, Some(FullscreenMode::Maximized));
+      let exited = dispatcher.resolve_fullscreen_mode(exited).await.unwrap();
+      assert_eq!(exited.mode, Some(FullscreenMode::None));
+    })
+  }
+
+  const MONITORS: &str = r#"[{
+    "id": 0,
+    "name": "DP-2",
+    "description": "Dell Inc. DELL U2720Q 8XYZ123",
+    "make": "Dell Inc.",
+    "model": "DELL U2720Q",
+    "serial": "8XYZ123",
+    "width": 3840,
+    "height": 2160,
+    "refreshRate": 59.99700,
+    "x": 0,
+    "y": 0,
+    "activeWorkspace": { "id": 1, "name": "1" },
+    "specialWorkspace": { "id": 0, "name": "" },
+    "reserved": [0, 30, 0, 0],
+    "scale": 1.50,
+    "transform": 0,
+    "focused": true,
+    "dpmsStatus": true,
+    "vrr": false,
+    "disabled": false
+  }, {
+    "id": 1,
+    "name": "eDP-1",
+    "description": "BOE 0x095F (eDP-1)",
+    "width": 1920,
+    "height": 1080
+  }]"#;
+
+  #[test]
+  fn monitor_descriptions() {
+    block_on(async {
+      let (hyprland, _) = fake::serve(|request| match request {
+        "j/monitors" => MONITORS.to_string(),
+        _ => "unknown request".to_string(),
+      });
+      let monitors = Dispatcher::new(hyprland).monitors().await.unwrap();
+
+      let dell = monitors[0].description();
+      assert_eq!(dell.model, "DELL U2720Q");
+      assert_eq!(dell.serial.as_deref(), Some("8XYZ123"));
+      assert_eq!(monitors[0].active_workspace.id, 1);
+
+      let panel = monitors[1].description();
+      assert_eq!(panel.make, "BOE");
+      assert_eq!(panel.model, "0x095F");
+      assert_eq!(panel.serial, None);
+      assert_eq!(panel.connector.as_deref(), Some("eDP-1"));
+    })
+  }
+}
@@ -0,0 +1,18 @@
+//! The executor specific parts of the async API: socket I/O, spawning the listener task and
+//! timers. Everything else is written against this module, so it works the same on every backend.
+//!
+//! One backend is compiled: tokio with the `async-tokio` feature, otherwise smol with
+//! `async-smol`. Both provide the same items.
+
+#[cfg(feature = "async-tokio")]
+mod tokio;
+#[cfg(feature = "async-tokio")]
+pub use self::tokio::*;
+
+#[cfg(all(feature = "async-smol", not(feature = "async-tokio")))]
+mod smol;
+#[cfg(all(feature = "async-smol", not(feature = "async-tokio")))]
+pub use self::smol::*;
+
+#[cfg(not(any(feature = "async-tokio", feature = "async-smol")))]
+compile_error!("the async API needs a runtime, enable the `async-tokio` or `async-smol` feature");
@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+pub(crate) use ::smol::io::AsyncRead;
+use ::smol::io::{AsyncReadExt, AsyncWriteExt};
+pub(crate) use ::smol::net::unix::UnixStream;
+
+pub(crate) async fn connect(path: &str) -> io::Result<UnixStream> {
+  UnixStream::connect(path).await
+}
+
+/// Writes `request` to a new connection to `path` and reads the reply until the peer closes it.
+pub(crate) async fn request(path: &str, request: &str) -> io::Result<String> {
+  let mut stream = UnixStream::connect(path).await?;
+  stream.write_all(request.as_bytes()).await?;
+
+  let mut reply = String::new();
+  stream.read_to_string(&mut reply).await?;
+  Ok(reply)
+}
+
+pub(crate) async fn read<R: AsyncRead + Unpin>(
+  reader: &mut R,
+  buf: &mut [u8],
+) -> io::Result<usize> {
+  reader.read(buf).await
+}
+
+pub(crate) fn spawn<F>(future: F) -> Task
+where
+  F: Future<Output = ()> + Send + 'static,
+{
+  Task(Some(::smol::spawn(future)))
+}
+
+/// The task reading events for a [`Listener`](crate::Listener). Awaiting it waits until the
+/// socket closes; dropping it leaves the task running.
+pub struct Task(Option<::smol::Task<()>>);
+
+impl Future for Task {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+    match self.0.as_mut() {
+      Some(task) => Pin::new(task).poll(cx),
+      None => Poll::Ready(()),
+    }
+  }
+}
+
+/// smol cancels a task when its handle is dropped, tokio lets it run. Detach to behave the same.
+impl Drop for Task {
+  fn drop(&mut self) {
+    if let Some(task) = self.0.take() {
+      task.detach();
+    }
+  }
+}
+
+/// A timer that can be moved to another instant without being recreated.
+pub(crate) struct Timer(::smol::Timer);
+
+impl Timer {
+  pub(crate) fn at(at: Instant) -> Self {
+    Timer(::smol::Timer::at(at))
+  }
+
+  pub(crate) fn reset(&mut self, at: Instant) {
+    self.0.set_at(at);
+  }
+
+  pub(crate) fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+    Pin::new(&mut self.0).poll(cx).map(|_| ())
+  }
+}
+
+/// Runs `future` to completion on the calling thread.
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+  ::smol::block_on(future)
+}
+
+#[cfg(test)]
+pub(crate) async fn sleep(duration: std::time::Duration) {
+  ::smol::Timer::after(duration).await;
+}
+
+/// A reader returning `data` at most `size` bytes at a time, to test framing across reads.
+#[cfg(test)]
+pub(crate) struct Chunks {
+  data: &'static [u8],
+  size: usize,
+}
+
+#[cfg(test)]
+impl Chunks {
+  pub(crate) fn new(data: &'static [u8], size: usize) -> Self {
+    Chunks { data, size }
+  }
+}
+
+#[cfg(test)]
+impl AsyncRead for Chunks {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+    buf: &mut [u8],
+  ) -> Poll<io::Result<usize>> {
+    let n = self.size.min(self.data.len()).min(buf.len());
+    buf[..n].copy_from_slice(&self.data[..n]);
+    self.data = &self.data[n..];
+    Poll::Ready(Ok(n))
+  }
+}
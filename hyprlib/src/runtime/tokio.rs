@@ -0,0 +1,112 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::Instant;
+
+pub(crate) use ::tokio::io::AsyncRead;
+use ::tokio::io::{AsyncReadExt, AsyncWriteExt};
+pub(crate) use ::tokio::net::UnixStream;
+
+pub(crate) async fn connect(path: &str) -> io::Result<UnixStream> {
+  UnixStream::connect(path).await
+}
+
+/// Writes `request` to a new connection to `path` and reads the reply until the peer closes it.
+pub(crate) async fn request(path: &str, request: &str) -> io::Result<String> {
+  let mut stream = UnixStream::connect(path).await?;
+  stream.write_all(request.as_bytes()).await?;
+
+  let mut reply = String::new();
+  stream.read_to_string(&mut reply).await?;
+  Ok(reply)
+}
+
+pub(crate) async fn read<R: AsyncRead + Unpin>(
+  reader: &mut R,
+  buf: &mut [u8],
+) -> io::Result<usize> {
+  reader.read(buf).await
+}
+
+pub(crate) fn spawn<F>(future: F) -> Task
+where
+  F: Future<Output = ()> + Send + 'static,
+{
+  Task(::tokio::spawn(future))
+}
+
+/// The task reading events for a [`Listener`](crate::Listener). Awaiting it waits until the
+/// socket closes; dropping it leaves the task running.
+pub struct Task(::tokio::task::JoinHandle<()>);
+
+impl Future for Task {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+    match ready!(Pin::new(&mut self.0).poll(cx)) {
+      Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+      _ => Poll::Ready(()),
+    }
+  }
+}
+
+/// A timer that can be moved to another instant without being recreated.
+pub(crate) struct Timer(Pin<Box<::tokio::time::Sleep>>);
+
+impl Timer {
+  pub(crate) fn at(at: Instant) -> Self {
+    Timer(Box::pin(::tokio::time::sleep_until(at.into())))
+  }
+
+  pub(crate) fn reset(&mut self, at: Instant) {
+    self.0.as_mut().reset(at.into());
+  }
+
+  pub(crate) fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+    self.0.as_mut().poll(cx)
+  }
+}
+
+/// Runs `future` to completion on a runtime made for the test.
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+  ::tokio::runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()
+    .unwrap()
+    .block_on(future)
+}
+
+#[cfg(test)]
+pub(crate) async fn sleep(duration: std::time::Duration) {
+  ::tokio::time::sleep(duration).await
+}
+
+/// A reader returning `data` at most `size` bytes at a time, to test framing across reads.
+#[cfg(test)]
+pub(crate) struct Chunks {
+  data: &'static [u8],
+  size: usize,
+}
+
+#[cfg(test)]
+impl Chunks {
+  pub(crate) fn new(data: &'static [u8], size: usize) -> Self {
+    Chunks { data, size }
+  }
+}
+
+#[cfg(test)]
+impl AsyncRead for Chunks {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+    buf: &mut ::tokio::io::ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    let n = self.size.min(self.data.len()).min(buf.remaining());
+    buf.put_slice(&self.data[..n]);
+    self.data = &self.data[n..];
+    Poll::Ready(Ok(()))
+  }
+}
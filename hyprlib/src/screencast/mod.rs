@@ -0,0 +1,153 @@
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+#[cfg(feature = "async")]
+use futures_core::Stream;
+
+#[cfg(feature = "async")]
+use crate::envelope::Envelope;
+use crate::events::{Event, ScreencastOwner};
+
+/// `ScreencastTracker` turns `screencast` events into a level: whether anything is being shared
+/// right now.
+///
+/// Hyprland sends one start and one stop per screencopy session, and several sessions can overlap,
+/// for example a call sharing a monitor while a recorder captures a window. The tracker counts
+/// them per owner. Stops without a matching start, as seen when tracking begins mid-session, are
+/// ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScreencastTracker {
+  monitor: usize,
+  window: usize,
+}
+
+impl ScreencastTracker {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Applies an event. Returns the new level when it changed, `None` otherwise.
+  pub fn apply(&mut self, event: &Event) -> Option<bool> {
+    let Event::Screencast(screencast) = event else {
+      return None;
+    };
+
+    let was_sharing = self.is_sharing();
+    let sessions = match screencast.owner {
+      ScreencastOwner::Monitor => &mut self.monitor,
+      ScreencastOwner::Window => &mut self.window,
+    };
+    *sessions = match screencast.state {
+      true => *sessions + 1,
+      false => sessions.saturating_sub(1),
+    };
+
+    let sharing = self.is_sharing();
+    (sharing != was_sharing).then_some(sharing)
+  }
+
+  /// Whether at least one screencopy session is active.
+  pub fn is_sharing(&self) -> bool {
+    self.sessions() > 0
+  }
+
+  /// Number of active screencopy sessions.
+  pub fn sessions(&self) -> usize {
+    self.monitor + self.window
+  }
+
+  /// Number of active screencopy sessions capturing `owner`.
+  pub fn sessions_of(&self, owner: ScreencastOwner) -> usize {
+    match owner {
+      ScreencastOwner::Monitor => self.monitor,
+      ScreencastOwner::Window => self.window,
+    }
+  }
+}
+
+/// Stream returned by
+/// [`EventStreamExt::screen_sharing`](crate::stream::EventStreamExt::screen_sharing).
+///
+/// Each item carries the envelope of the `screencast` event that changed the level.
+#[cfg(feature = "async")]
+pub struct ScreenSharing<S> {
+  inner: S,
+  tracker: ScreencastTracker,
+}
+
+#[cfg(feature = "async")]
+impl<S> ScreenSharing<S> {
+  pub(crate) fn new(inner: S) -> Self {
+    ScreenSharing {
+      inner,
+      tracker: ScreencastTracker::new(),
+    }
+  }
+
+  /// The tracker behind the stream, to read session counts.
+  pub fn tracker(&self) -> &ScreencastTracker {
+    &self.tracker
+  }
+}
+
+#[cfg(feature = "async")]
+impl<S> Stream for ScreenSharing<S>
+where
+  S: Stream<Item = Envelope> + Unpin,
+{
+  type Item = Envelope<bool>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Envelope<bool>>> {
+    let this = &mut *self;
+    loop {
+      match Pin::new(&mut this.inner).poll_next(cx) {
+        Poll::Ready(Some(envelope)) => {
+          if let Some(sharing) = this.tracker.apply(&envelope.event) {
+            return Poll::Ready(Some(envelope.map(|_| sharing)));
+          }
+        }
+        other => return other.map(|_| None),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::events::Screencast;
+
+  fn screencast(state: bool, owner: ScreencastOwner) -> Event {
+    Event::Screencast(Screencast { state, owner })
+  }
+
+  #[test]
+  fn level_changes_only_on_first_start_and_last_stop() {
+    let mut tracker = ScreencastTracker::new();
+    assert_eq!(
+      tracker.apply(&screencast(false, ScreencastOwner::Window)),
+      None
+    );
+    assert_eq!(
+      tracker.apply(&screencast(true, ScreencastOwner::Monitor)),
+      Some(true)
+    );
+    assert_eq!(
+      tracker.apply(&screencast(true, ScreencastOwner::Window)),
+      None
+    );
+    assert_eq!(tracker.sessions(), 2);
+    assert_eq!(
+      tracker.apply(&screencast(false, ScreencastOwner::Monitor)),
+      None
+    );
+    assert_eq!(tracker.sessions_of(ScreencastOwner::Window), 1);
+    assert_eq!(
+      tracker.apply(&screencast(false, ScreencastOwner::Window)),
+      Some(false)
+    );
+    assert!(!tracker.is_sharing());
+  }
+}
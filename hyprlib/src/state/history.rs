@@ -0,0 +1,234 @@
+//! The most recently used order of windows, for alt-tab style switching.
+//!
+//! Hyprland's `focuscurrentorlast` only goes one window back. [`State::focus_history`] keeps
+//! every window instead, updated from `activewindowv2`, `openwindow` and `closewindow`, and
+//! [`Dispatcher::cycle`] focuses any window in it.
+
+use super::{State, Window};
+use crate::events::WindowAddress;
+use crate::query::Client;
+#[cfg(feature = "async")]
+use crate::Dispatcher;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::{Dispatch, DispatchError};
+
+/// Which windows a walk through the focus history goes over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryScope {
+  /// Every window.
+  Global,
+  /// The windows on the focused workspace.
+  Workspace,
+}
+
+/// The history Hyprland reports through `focusHistoryID`, where 0 is the most recent window.
+pub(super) fn from_clients(clients: &[Client]) -> Vec<WindowAddress> {
+  let mut clients: Vec<&Client> = clients.iter().collect();
+  clients.sort_by_key(|client| (client.focus_history_id < 0, client.focus_history_id));
+  clients.iter().map(|client| client.address).collect()
+}
+
+pub(super) fn focused(history: &mut Vec<WindowAddress>, address: WindowAddress) {
+  history.retain(|other| *other != address);
+  history.insert(0, address);
+}
+
+/// Windows that were never focused go last.
+pub(super) fn opened(history: &mut Vec<WindowAddress>, address: WindowAddress) {
+  if !history.contains(&address) {
+    history.push(address);
+  }
+}
+
+pub(super) fn closed(history: &mut Vec<WindowAddress>, address: WindowAddress) {
+  history.retain(|other| *other != address);
+}
+
+impl State {
+  /// The windows in `scope`, from the most to the least recently focused. The focused window
+  /// comes first, unless focus is on an empty workspace or a layer surface.
+  pub fn focus_history(&self, scope: HistoryScope) -> impl Iterator<Item = &Window> {
+    let workspace_id = match scope {
+      HistoryScope::Global => None,
+      HistoryScope::Workspace => Some(self.focused_workspace),
+    };
+    self
+      .focus_history
+      .iter()
+      .filter_map(|address| self.windows.get(address))
+      .filter(move |window| workspace_id.is_none_or(|id| id == Some(window.workspace_id)))
+  }
+
+  /// The window `n` steps back from the focused one in the history of `scope`. 1 is the
+  /// previously focused window; past the least recent one it wraps around, skipping the focused
+  /// window. `None` for 0, or when no other window is in `scope`.
+  ///
+  /// An alt-tab switcher keeps the snapshot it started with and asks for 1, 2, 3... while the
+  /// key is held, as every focus change reorders the history.
+  pub fn cycle_target(&self, n: usize, scope: HistoryScope) -> Option<&Window> {
+    let others: Vec<&Window> = self
+      .focus_history(scope)
+      .filter(|window| Some(window.address) != self.focused_window)
+      .collect();
+    let index = n.checked_sub(1)?;
+    (!others.is_empty()).then(|| others[index % others.len()])
+  }
+}
+
+#[cfg(feature = "async")]
+impl Dispatcher {
+  /// Focuses the window focused before the current one. Returns its address, `None` when there
+  /// is no other window in `scope`.
+  pub async fn focus_previous(
+    &self,
+    state: &State,
+    scope: HistoryScope,
+  ) -> Result<Option<WindowAddress>, DispatchError> {
+    self.cycle(state, 1, scope).await
+  }
+
+  /// Focuses the window `n` steps back in the focus history, see [`State::cycle_target`].
+  /// Returns its address, `None` when there is no window to go to.
+  pub async fn cycle(
+    &self,
+    state: &State,
+    n: usize,
+    scope: HistoryScope,
+  ) -> Result<Option<WindowAddress>, DispatchError> {
+    match cycle_dispatch(state, n, scope) {
+      Some((address, dispatch)) => {
+        self.dispatch(dispatch).await?;
+        Ok(Some(address))
+      }
+      None => Ok(None),
+    }
+  }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::Dispatcher {
+  /// Focuses the window focused before the current one, see the async `Dispatcher::focus_previous`.
+  pub fn focus_previous(
+    &self,
+    state: &State,
+    scope: HistoryScope,
+  ) -> Result<Option<WindowAddress>, DispatchError> {
+    self.cycle(state, 1, scope)
+  }
+
+  /// Focuses the window `n` steps back in the focus history, see [`State::cycle_target`].
+  pub fn cycle(
+    &self,
+    state: &State,
+    n: usize,
+    scope: HistoryScope,
+  ) -> Result<Option<WindowAddress>, DispatchError> {
+    match cycle_dispatch(state, n, scope) {
+      Some((address, dispatch)) => {
+        self.dispatch(dispatch)?;
+        Ok(Some(address))
+      }
+      None => Ok(None),
+    }
+  }
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+fn cycle_dispatch(
+  state: &State,
+  n: usize,
+  scope: HistoryScope,
+) -> Option<(WindowAddress, Dispatch)> {
+  let address = state.cycle_target(n, scope)?.address;
+  Some((address, Dispatch::FocusWindow(address)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::events::Event;
+  use crate::state::Queries;
+
+  fn state() -> State {
+    let queries = Queries {
+      clients: serde_json::from_str(
+        r#"[
+          { "address": "0x1a", "workspace": { "id": 1, "name": "1" }, "focusHistoryID": 2 },
+          { "address": "0x2b", "workspace": { "id": 2, "name": "2" }, "focusHistoryID": 0 },
+          { "address": "0x3c", "workspace": { "id": 1, "name": "1" }, "focusHistoryID": 1 }
+        ]"#,
+      )
+      .unwrap(),
+      ..Queries::default()
+    };
+    let mut state = State::from_queries(&queries);
+    state.focused_window = Some(WindowAddress(0x2b));
+    state.focused_workspace = Some(2);
+    state
+  }
+
+  fn addresses<'a>(windows: impl Iterator<Item = &'a Window>) -> Vec<u64> {
+    windows.map(|window| window.address.0).collect()
+  }
+
+  #[test]
+  fn history_survives_closing_windows() {
+    let mut state = state();
+    assert_eq!(
+      addresses(state.focus_history(HistoryScope::Global)),
+      [0x2b, 0x3c, 0x1a]
+    );
+
+    for (name, data) in [
+      ("workspacev2", "1,1"),
+      ("openwindow", "4d,1,kitty,~"),
+      ("activewindowv2", "1a"),
+      ("closewindow", "3c"),
+    ] {
+      state.apply(&Event::from(name, data));
+    }
+    assert_eq!(
+      addresses(state.focus_history(HistoryScope::Global)),
+      [0x1a, 0x2b, 0x4d]
+    );
+    assert_eq!(
+      addresses(state.focus_history(HistoryScope::Workspace)),
+      [0x1a, 0x4d]
+    );
+  }
+
+  #[test]
+  fn cycle_wraps_around_the_focused_window() {
+    let mut state = state();
+    let target = |state: &State, n, scope| state.cycle_target(n, scope).map(|w| w.address.0);
+    assert_eq!(target(&state, 1, HistoryScope::Global), Some(0x3c));
+    assert_eq!(target(&state, 2, HistoryScope::Global), Some(0x1a));
+    assert_eq!(target(&state, 3, HistoryScope::Global), Some(0x3c));
+    assert_eq!(target(&state, 0, HistoryScope::Global), None);
+    assert_eq!(target(&state, 1, HistoryScope::Workspace), None);
+
+    // Focus on an empty workspace: the most recent window is one step back.
+    state.apply(&Event::from("activewindowv2", ""));
+    assert_eq!(target(&state, 1, HistoryScope::Global), Some(0x2b));
+    assert_eq!(target(&state, 3, HistoryScope::Global), Some(0x1a));
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn focus_previous_dispatches_focuswindow() {
+    crate::runtime::block_on(async {
+      let (hyprland, requests) = crate::dispatch::fake::serve(|_| "ok".to_string());
+      let dispatcher = Dispatcher::new(hyprland);
+
+      let focused = dispatcher
+        .focus_previous(&state(), HistoryScope::Global)
+        .await
+        .unwrap();
+      assert_eq!(focused, Some(WindowAddress(0x3c)));
+      assert_eq!(
+        requests.lock().unwrap()[0],
+        "dispatch focuswindow address:0x3c"
+      );
+    })
+  }
+}
@@ -0,0 +1,917 @@
+//! A model of the compositor, built from query replies and kept up to date from events.
+//!
+//! [`State`] is plain data: build it with [`State::from_queries`] and feed it events with
+//! [`State::apply`]. [`HyprState`] shares one between whoever applies the events and any number of
+//! readers, who get consistent snapshots as an `Arc<State>`.
+//!
+//! ```no_run
+//! use hyprlib::state::HyprState;
+//! use hyprlib::{Dispatcher, Hyprland, Listener};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let listener = Listener::new(Hyprland::default());
+//! let dispatcher = Dispatcher::new(Hyprland::default());
+//! let (state, _task) = HyprState::connect(&listener, &dispatcher).await?;
+//! listener.listen().await?;
+//!
+//! let snapshot = state.snapshot();
+//! if let Some(window) = snapshot.focused_window() {
+//!   println!("{} on workspace {}", window.title, window.workspace_id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod history;
+mod reconcile;
+#[cfg(feature = "async")]
+mod seed;
+mod watch;
+
+pub use history::HistoryScope;
+pub use reconcile::{Change, Correction};
+#[cfg(feature = "async")]
+pub use reconcile::{Corrections, Reconciler};
+pub use watch::{Closed, Watch};
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+
+use tracing::debug;
+
+use crate::envelope::Envelope;
+use crate::events::{Event, EventKind, EventKinds, FullscreenMode, WindowAddress};
+#[cfg(feature = "async")]
+use crate::listener::Cursor;
+use crate::query::{self, Client, Devices};
+#[cfg(feature = "async")]
+use crate::{runtime, DispatchError, Dispatcher, Listener, Subscription, Task};
+#[cfg(feature = "async")]
+use seed::Seeder;
+
+/// A window as far as events tell.
+///
+/// # Fields
+///
+/// * `address` - The address of the window.
+/// * `workspace_id` - The ID of the workspace the window is on.
+/// * `class` - The class of the window.
+/// * `title` - The title of the window.
+/// * `floating` - Whether the window is floating. `openwindow` doesn't tell, so a window a rule
+///   opened floating counts as tiled until [`State::seed_window`] ran for it.
+/// * `pinned` - Whether the window is pinned.
+/// * `fullscreen` - Whether the window is fullscreen or maximized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Window {
+  pub address: WindowAddress,
+  pub workspace_id: i32,
+  pub class: String,
+  pub title: String,
+  pub floating: bool,
+  pub pinned: bool,
+  pub fullscreen: bool,
+}
+
+impl From<&Client> for Window {
+  fn from(client: &Client) -> Self {
+    Window {
+      address: client.address,
+      workspace_id: client.workspace.id,
+      class: client.class.clone(),
+      title: client.title.clone(),
+      floating: client.floating,
+      pinned: client.pinned,
+      fullscreen: client.fullscreen != FullscreenMode::None,
+    }
+  }
+}
+
+/// A workspace as far as events tell.
+///
+/// # Fields
+///
+/// * `id` - The ID of the workspace. Special workspaces have negative IDs.
+/// * `name` - The name of the workspace.
+/// * `monitor` - The name of the monitor the workspace is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+  pub id: i32,
+  pub name: String,
+  pub monitor: String,
+}
+
+impl From<&query::Workspace> for Workspace {
+  fn from(workspace: &query::Workspace) -> Self {
+    Workspace {
+      id: workspace.id,
+      name: workspace.name.clone(),
+      monitor: workspace.monitor.clone(),
+    }
+  }
+}
+
+/// A monitor as far as events tell.
+///
+/// # Fields
+///
+/// * `id` - The ID of the monitor.
+/// * `name` - The name of the monitor's connector, such as `DP-1`.
+/// * `description` - The description of the display plugged into it.
+/// * `active_workspace_id` - The workspace shown on the monitor, `None` until an event tells.
+/// * `special_workspace_id` - The special workspace opened on the monitor, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monitor {
+  pub id: i32,
+  pub name: String,
+  pub description: String,
+  pub active_workspace_id: Option<i32>,
+  pub special_workspace_id: Option<i32>,
+}
+
+impl From<&query::Monitor> for Monitor {
+  fn from(monitor: &query::Monitor) -> Self {
+    Monitor {
+      id: monitor.id,
+      name: monitor.name.clone(),
+      description: monitor.description.clone(),
+      active_workspace_id: Some(monitor.active_workspace.id),
+      special_workspace_id: match monitor.special_workspace.id {
+        0 => None,
+        id => Some(id),
+      },
+    }
+  }
+}
+
+/// The query replies a [`State`] is built from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Queries {
+  pub clients: Vec<Client>,
+  pub workspaces: Vec<query::Workspace>,
+  pub monitors: Vec<query::Monitor>,
+  pub devices: Devices,
+  pub active_window: Option<Client>,
+}
+
+/// The windows, workspaces and monitors of the compositor, what has focus, the active submap and
+/// the layout of every keyboard.
+///
+/// Only what events report is kept up to date, so a window's size or position aren't part of it;
+/// query them when needed.
+///
+/// # Fields
+///
+/// * `seq` - The sequence number of the last envelope applied by [`HyprState`], 0 for a state
+///   built from queries.
+/// * `windows` - The windows, by address.
+/// * `workspaces` - The workspaces, by ID.
+/// * `monitors` - The monitors, by name.
+/// * `focused_window` - The address of the focused window.
+/// * `focused_workspace` - The ID of the focused workspace.
+/// * `focused_monitor` - The name of the focused monitor.
+/// * `focus_history` - Every window, from the most to the least recently focused. Windows that
+///   were never focused come last, see [`focus_history`](State::focus_history).
+/// * `submap` - The active keybind submap, empty for the default one.
+/// * `keyboard_layouts` - The active layout of every keyboard, by keyboard name.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct State {
+  pub seq: u64,
+  pub windows: BTreeMap<WindowAddress, Window>,
+  pub workspaces: BTreeMap<i32, Workspace>,
+  pub monitors: BTreeMap<String, Monitor>,
+  pub focused_window: Option<WindowAddress>,
+  pub focused_workspace: Option<i32>,
+  pub focused_monitor: Option<String>,
+  pub focus_history: Vec<WindowAddress>,
+  pub submap: String,
+  pub keyboard_layouts: BTreeMap<String, String>,
+}
+
+impl State {
+  /// The kinds of events [`apply`](State::apply) uses, to narrow a subscription to.
+  pub const KINDS: EventKinds = EventKinds::of(&[
+    EventKind::WorkspaceV2,
+    EventKind::FocusedMon,
+    EventKind::ActiveWindowV2,
+    EventKind::Fullscreen,
+    EventKind::MonitorRemoved,
+    EventKind::MonitorRemovedV2,
+    EventKind::MonitorAddedV2,
+    EventKind::CreateWorkspaceV2,
+    EventKind::DestroyWorkspaceV2,
+    EventKind::MoveWorkspaceV2,
+    EventKind::RenameWorkspace,
+    EventKind::ActiveSpecialV2,
+    EventKind::ActiveLayout,
+    EventKind::OpenWindow,
+    EventKind::CloseWindow,
+    EventKind::MoveWindowV2,
+    EventKind::WindowTitleV2,
+    EventKind::Submap,
+    EventKind::ChangeFloatingMode,
+    EventKind::Pin,
+  ]);
+
+  /// Builds the state from query replies. Older Hyprland releases can't report the submap, so it
+  /// isn't among the queries and starts out as the default one.
+  pub fn from_queries(queries: &Queries) -> Self {
+    let focused_monitor = queries.monitors.iter().find(|monitor| monitor.focused);
+    State {
+      seq: 0,
+      windows: queries
+        .clients
+        .iter()
+        .map(|client| (client.address, Window::from(client)))
+        .collect(),
+      workspaces: queries
+        .workspaces
+        .iter()
+        .map(|workspace| (workspace.id, Workspace::from(workspace)))
+        .collect(),
+      monitors: queries
+        .monitors
+        .iter()
+        .map(|monitor| (monitor.name.clone(), Monitor::from(monitor)))
+        .collect(),
+      focused_window: queries.active_window.as_ref().map(|client| client.address),
+      focused_workspace: focused_monitor.map(|monitor| monitor.active_workspace.id),
+      focused_monitor: focused_monitor.map(|monitor| monitor.name.clone()),
+      focus_history: history::from_clients(&queries.clients),
+      submap: String::new(),
+      keyboard_layouts: queries
+        .devices
+        .keyboards
+        .iter()
+        .map(|keyboard| (keyboard.name.clone(), keyboard.active_keymap.clone()))
+        .collect(),
+    }
+  }
+
+  pub fn focused_window(&self) -> Option<&Window> {
+    self.windows.get(&self.focused_window?)
+  }
+
+  pub fn focused_workspace(&self) -> Option<&Workspace> {
+    self.workspaces.get(&self.focused_workspace?)
+  }
+
+  pub fn focused_monitor(&self) -> Option<&Monitor> {
+    self.monitors.get(self.focused_monitor.as_ref()?)
+  }
+
+  pub fn workspace_by_name(&self, name: &str) -> Option<&Workspace> {
+    self
+      .workspaces
+      .values()
+      .find(|workspace| workspace.name == name)
+  }
+
+  /// The windows on the workspace with ID `workspace_id`.
+  pub fn windows_on(&self, workspace_id: i32) -> impl Iterator<Item = &Window> {
+    self
+      .windows
+      .values()
+      .filter(move |window| window.workspace_id == workspace_id)
+  }
+
+  /// Updates the state with an event. Events of kinds outside [`State::KINDS`] are ignored; the
+  /// v1 events Hyprland sends next to their v2 versions are among them.
+  ///
+  /// Applying an event the state already reflects changes nothing, so events received while the
+  /// queries ran can be applied on top of their replies.
+  pub fn apply(&mut self, event: &Event) {
+    match event {
+      Event::WorkspaceV2(event) => {
+        self.focused_workspace = Some(event.workspace_id);
+        let monitor = self.focused_monitor.clone().unwrap_or_default();
+        self
+          .workspaces
+          .entry(event.workspace_id)
+          .or_insert_with(|| Workspace {
+            id: event.workspace_id,
+            name: event.workspace_name.clone(),
+            monitor: monitor.clone(),
+          });
+        if let Some(monitor) = self.monitors.get_mut(&monitor) {
+          monitor.active_workspace_id = Some(event.workspace_id);
+        }
+      }
+      Event::FocusedMon(event) => {
+        let workspace_id = self
+          .workspace_by_name(&event.workspace_name)
+          .map(|workspace| workspace.id);
+        self.focused_monitor = Some(event.monitor_name.clone());
+        if workspace_id.is_some() {
+          self.focused_workspace = workspace_id;
+        }
+        if let Some(monitor) = self.monitors.get_mut(&event.monitor_name) {
+          monitor.active_workspace_id = workspace_id.or(monitor.active_workspace_id);
+        }
+      }
+      Event::ActiveWindowV2(event) => {
+        self.focused_window = event.window_address;
+        if let Some(address) = event.window_address {
+          history::focused(&mut self.focus_history, address);
+        }
+      }
+      Event::Fullscreen(event) => {
+        if let Some(window) = self
+          .focused_window
+          .and_then(|address| self.windows.get_mut(&address))
+        {
+          window.fullscreen = event.enter_fullscreen;
+        }
+      }
+      Event::MonitorRemoved(event) => self.remove_monitor(&event.monitor_name),
+      Event::MonitorRemovedV2(event) => self.remove_monitor(&event.monitor_name),
+      Event::MonitorAddedV2(event) => {
+        let monitor = self
+          .monitors
+          .entry(event.monitor_name.clone())
+          .or_insert_with(|| Monitor {
+            id: 0,
+            name: event.monitor_name.clone(),
+            description: String::new(),
+            active_workspace_id: None,
+            special_workspace_id: None,
+          });
+        monitor.id = event.monitor_id as i32;
+        monitor.description = event.monitor_description.clone();
+      }
+      Event::CreateWorkspaceV2(event) => {
+        let monitor = self.focused_monitor.clone().unwrap_or_default();
+        self
+          .workspaces
+          .entry(event.workspace_id)
+          .or_insert_with(|| Workspace {
+            id: event.workspace_id,
+            name: event.workspace_name.clone(),
+            monitor,
+          });
+      }
+      Event::DestroyWorkspaceV2(event) => {
+        self.workspaces.remove(&event.workspace_id);
+        if self.focused_workspace == Some(event.workspace_id) {
+          self.focused_workspace = None;
+        }
+        // The next `workspacev2` or `focusedmon` tells what the monitor shows instead.
+        for monitor in self.monitors.values_mut() {
+          if monitor.active_workspace_id == Some(event.workspace_id) {
+            monitor.active_workspace_id = None;
+          }
+          if monitor.special_workspace_id == Some(event.workspace_id) {
+            monitor.special_workspace_id = None;
+          }
+        }
+      }
+      Event::MoveWorkspaceV2(event) => {
+        if let Some(workspace) = self.workspaces.get_mut(&event.workspace_id) {
+          workspace.monitor = event.monitor_name.clone();
+        }
+      }
+      Event::RenameWorkspace(event) => {
+        if let Some(workspace) = self.workspaces.get_mut(&event.workspace_id) {
+          workspace.name = event.new_name.clone();
+        }
+      }
+      Event::ActiveSpecialV2(event) => {
+        if let Some(monitor) = self.monitors.get_mut(&event.monitor_name) {
+          monitor.special_workspace_id = event.workspace_id;
+        }
+      }
+      Event::ActiveLayout(event) => {
+        self
+          .keyboard_layouts
+          .insert(event.keyboard_name.clone(), event.layout_name.clone());
+      }
+      Event::OpenWindow(event) => {
+        let workspace_id = self
+          .workspace_by_name(&event.workspace_name)
+          .map(|workspace| workspace.id)
+          .or(self.focused_workspace)
+          .unwrap_or_default();
+        // A window already known is as new as the event or newer, only its class can be missing.
+        self
+          .windows
+          .entry(event.window_address)
+          .and_modify(|window| {
+            if window.class.is_empty() {
+              window.class = event.window_class.clone();
+            }
+          })
+          .or_insert_with(|| Window {
+            address: event.window_address,
+            workspace_id,
+            class: event.window_class.clone(),
+            title: event.window_title.clone(),
+            floating: false,
+            pinned: false,
+            fullscreen: false,
+          });
+        history::opened(&mut self.focus_history, event.window_address);
+      }
+      Event::CloseWindow(event) => {
+        self.windows.remove(&event.window_address);
+        history::closed(&mut self.focus_history, event.window_address);
+        if self.focused_window == Some(event.window_address) {
+          self.focused_window = None;
+        }
+      }
+      Event::MoveWindowV2(event) => {
+        if let Some(window) = self.windows.get_mut(&event.window_address) {
+          window.workspace_id = event.workspace_id;
+        }
+      }
+      Event::WindowTitleV2(event) => {
+        if let Some(window) = self.windows.get_mut(&event.window_address) {
+          window.title = event.window_title.clone();
+        }
+      }
+      Event::Submap(event) => self.submap = event.submap_name.clone(),
+      Event::ChangeFloatingMode(event) => {
+        if let Some(window) = self.windows.get_mut(&event.window_address) {
+          window.floating = event.floating;
+        }
+      }
+      Event::Pin(event) => {
+        if let Some(window) = self.windows.get_mut(&event.window_address) {
+          window.pinned = event.pin_state;
+        }
+      }
+      _ => {}
+    }
+  }
+
+  /// Takes what `openwindow` doesn't tell from the `clients` reply for a window: whether it opened
+  /// floating, pinned or fullscreen, as window rules can make it. Unknown windows are ignored.
+  pub fn seed_window(&mut self, client: &Client) {
+    if let Some(window) = self.windows.get_mut(&client.address) {
+      window.floating = client.floating;
+      window.pinned = client.pinned;
+      window.fullscreen = client.fullscreen != FullscreenMode::None;
+    }
+  }
+
+  fn remove_monitor(&mut self, name: &str) {
+    self.monitors.remove(name);
+    if self.focused_monitor.as_deref() == Some(name) {
+      self.focused_monitor = None;
+    }
+  }
+}
+
+/// A [`State`] shared between the code applying events and its readers.
+///
+/// Cloning it is cheap and every clone sees the same state. [`snapshot`](HyprState::snapshot)
+/// hands out the current state without copying it; applying an event only copies the state while
+/// an older snapshot is still alive, so readers never see half applied events.
+///
+/// [`Watch`] handles follow values derived from it, such as the
+/// [focused window](HyprState::focused_window).
+#[derive(Clone, Debug, Default)]
+pub struct HyprState {
+  shared: Arc<Mutex<Shared>>,
+}
+
+/// What every clone of a [`HyprState`] and its watches share.
+///
+/// # Fields
+///
+/// * `state` - The current state.
+/// * `version` - Bumped on every update, so watches know when to derive their value again.
+/// * `closed` - Set once no more updates will come.
+/// * `wakers` - The watches waiting for the next update.
+/// * `cursor` - Where the listener being followed stands, once [`follow`](HyprState::follow) ran.
+#[derive(Debug, Default)]
+struct Shared {
+  state: Arc<State>,
+  version: u64,
+  closed: bool,
+  wakers: Vec<Waker>,
+  #[cfg(feature = "async")]
+  cursor: Option<Cursor>,
+}
+
+#[cfg(feature = "async")]
+impl Shared {
+  /// The last line the followed listener read, once every envelope it delivered up to that line
+  /// is applied; `None` while some are still on their way. Without a listener, the last applied
+  /// envelope.
+  fn mark(&self) -> Option<u64> {
+    match &self.cursor {
+      Some(cursor) => cursor
+        .settled()
+        .filter(|_| self.state.seq == cursor.delivered()),
+      None => Some(self.state.seq),
+    }
+  }
+}
+
+impl HyprState {
+  pub fn new(state: State) -> Self {
+    HyprState {
+      shared: Arc::new(Mutex::new(Shared {
+        state: Arc::new(state),
+        ..Shared::default()
+      })),
+    }
+  }
+
+  /// The state as of the last applied envelope.
+  pub fn snapshot(&self) -> Arc<State> {
+    self.shared.lock().unwrap().state.clone()
+  }
+
+  /// Applies the event of `envelope` and records its sequence number.
+  pub fn apply(&self, envelope: &Envelope) {
+    self.update(|state| {
+      state.apply(&envelope.event);
+      state.seq = envelope.seq;
+    });
+    debug!(seq = envelope.seq, "State updated");
+  }
+
+  /// Marks the state as no longer updated, which ends its watches once they have seen the last
+  /// value. `HyprState::follow` calls it when its subscription ends.
+  pub fn close(&self) {
+    let wakers = {
+      let mut shared = self.shared.lock().unwrap();
+      shared.closed = true;
+      std::mem::take(&mut shared.wakers)
+    };
+    wakers.into_iter().for_each(Waker::wake);
+  }
+
+  /// Runs `update` on the state and wakes the watches.
+  fn update<R>(&self, update: impl FnOnce(&mut State) -> R) -> R {
+    let (result, wakers) = {
+      let mut shared = self.shared.lock().unwrap();
+      let result = update(Arc::make_mut(&mut shared.state));
+      shared.version += 1;
+      (result, std::mem::take(&mut shared.wakers))
+    };
+    wakers.into_iter().for_each(Waker::wake);
+    result
+  }
+
+  /// Where the state stands, see `Shared::mark`.
+  #[cfg(feature = "async")]
+  fn mark(&self) -> Option<u64> {
+    self.shared.lock().unwrap().mark()
+  }
+
+  /// Like [`update`](HyprState::update), but only if the state still stands at `mark`: no line
+  /// was read since, and everything delivered is applied.
+  #[cfg(feature = "async")]
+  fn update_at<R>(&self, mark: u64, update: impl FnOnce(&mut State) -> R) -> Option<R> {
+    let (result, wakers) = {
+      let mut shared = self.shared.lock().unwrap();
+      if shared.mark() != Some(mark) {
+        return None;
+      }
+      let result = update(Arc::make_mut(&mut shared.state));
+      shared.version += 1;
+      (result, std::mem::take(&mut shared.wakers))
+    };
+    wakers.into_iter().for_each(Waker::wake);
+    Some(result)
+  }
+}
+
+#[cfg(feature = "async")]
+impl HyprState {
+  /// Subscribes to `listener`, queries the initial state through `dispatcher` and spawns the task
+  /// applying the events. Subscribing first means no event is missed while the queries run.
+  ///
+  /// The task ends once `listener` is dropped and its reading task has finished.
+  pub async fn connect(
+    listener: &Listener,
+    dispatcher: &Dispatcher,
+  ) -> Result<(Self, Task), DispatchError> {
+    let subscription = listener.subscribe().only(State::KINDS);
+    let state = HyprState::new(dispatcher.state().await?);
+    let task = state.follow_with(subscription, dispatcher.clone());
+    Ok((state, task))
+  }
+
+  /// Spawns a task applying every envelope `subscription` receives. The state is
+  /// [closed](HyprState::close) once the subscription ends.
+  pub fn follow(&self, subscription: Subscription) -> Task {
+    self.spawn_follow(subscription, None)
+  }
+
+  /// Like [`follow`](HyprState::follow), and also queries `clients` through `dispatcher` after
+  /// `openwindow` to [seed](State::seed_window) new windows. The queries run on a task of their
+  /// own, one for every burst of windows, so the events never wait for them.
+  pub fn follow_with(&self, subscription: Subscription, dispatcher: Dispatcher) -> Task {
+    self.spawn_follow(subscription, Some(dispatcher))
+  }
+
+  fn spawn_follow(&self, mut subscription: Subscription, dispatcher: Option<Dispatcher>) -> Task {
+    self.shared.lock().unwrap().cursor = Some(subscription.cursor());
+    let state = self.clone();
+    let seeder = dispatcher.map(|dispatcher| Seeder::spawn(state.clone(), dispatcher));
+    runtime::spawn(async move {
+      while let Some(envelope) = subscription.recv().await {
+        // A window known before its `openwindow` came from the queries or was seen already.
+        let new_window = match &envelope.event {
+          Event::OpenWindow(event) => Some(event.window_address)
+            .filter(|address| !state.snapshot().windows.contains_key(address)),
+          _ => None,
+        };
+        state.apply(&envelope);
+        if let (Some(seeder), Some(address)) = (&seeder, new_window) {
+          seeder.seed(address);
+        }
+      }
+      if let Some(seeder) = seeder {
+        seeder.finish().await;
+      }
+      state.close();
+    })
+  }
+}
+
+#[cfg(feature = "async")]
+impl Dispatcher {
+  /// Runs the queries a [`State`] is built from.
+  pub async fn state(&self) -> Result<State, DispatchError> {
+    let queries = Queries {
+      clients: self.clients().await?,
+      workspaces: self.workspaces().await?,
+      monitors: self.monitors().await?,
+      devices: self.devices().await?,
+      active_window: self.active_window().await?,
+    };
+    Ok(State::from_queries(&queries))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn apply(state: &mut State, lines: &[&str]) {
+    for line in lines {
+      let (name, data) = line.split_once(">>").unwrap();
+      state.apply(&Event::from(name, data));
+    }
+  }
+
+  fn initial() -> State {
+    let queries = Queries {
+      clients: serde_json::from_str(
+        r#"[{
+          "address": "0x1a",
+          "workspace": { "id": 1, "name": "1" },
+          "class": "kitty",
+          "title": "~"
+        }]"#,
+      )
+      .unwrap(),
+      workspaces: serde_json::from_str(r#"[{ "id": 1, "name": "1", "monitor": "DP-1" }]"#).unwrap(),
+      monitors: serde_json::from_str(
+        r#"[{
+          "id": 0,
+          "name": "DP-1",
+          "activeWorkspace": { "id": 1, "name": "1" },
+          "specialWorkspace": { "id": 0, "name": "" },
+          "focused": true
+        }]"#,
+      )
+      .unwrap(),
+      devices: serde_json::from_str(
+        r#"{ "keyboards": [{ "name": "kbd", "active_keymap": "English (US)" }] }"#,
+      )
+      .unwrap(),
+      active_window: None,
+    };
+    State::from_queries(&queries)
+  }
+
+  #[test]
+  fn windows_follow_events() {
+    let mut state = initial();
+    apply(
+      &mut state,
+      &[
+        "createworkspacev2>>2,web",
+        "workspacev2>>2,web",
+        "openwindow>>2b,web,firefox,Mozilla Firefox",
+        "activewindowv2>>2b",
+        "windowtitlev2>>2b,Rust - Mozilla Firefox",
+        "movewindowv2>>1a,2,web",
+        "changefloatingmode>>1a,1",
+        "fullscreen>>1",
+        "closewindow>>1a",
+      ],
+    );
+
+    assert_eq!(state.focused_workspace().unwrap().name, "web");
+    assert_eq!(
+      state.focused_monitor().unwrap().active_workspace_id,
+      Some(2)
+    );
+    let firefox = state.focused_window().unwrap();
+    assert_eq!(firefox.workspace_id, 2);
+    assert_eq!(firefox.title, "Rust - Mozilla Firefox");
+    assert!(firefox.fullscreen);
+    assert_eq!(state.windows_on(2).count(), 1);
+    assert!(!state.windows.contains_key(&WindowAddress(0x1a)));
+  }
+
+  #[test]
+  fn workspaces_monitors_and_input() {
+    let mut state = initial();
+    apply(
+      &mut state,
+      &[
+        "monitoraddedv2>>1,HDMI-A-1,Dell Inc. DELL U2720Q",
+        "createworkspacev2>>3,3",
+        "moveworkspacev2>>3,3,HDMI-A-1",
+        "renameworkspace>>3,chat",
+        "focusedmon>>HDMI-A-1,chat",
+        "activespecialv2>>-98,special:scratch,HDMI-A-1",
+        "activelayout>>kbd,German",
+        "submap>>resize",
+        "destroyworkspacev2>>1,1",
+        "monitorremoved>>DP-1",
+      ],
+    );
+
+    let hdmi = state.focused_monitor().unwrap();
+    assert_eq!(hdmi.id, 1);
+    assert_eq!(hdmi.active_workspace_id, Some(3));
+    assert_eq!(hdmi.special_workspace_id, Some(-98));
+    assert_eq!(state.workspace_by_name("chat").unwrap().monitor, "HDMI-A-1");
+    assert!(!state.workspaces.contains_key(&1));
+    assert!(!state.monitors.contains_key("DP-1"));
+    assert_eq!(state.keyboard_layouts["kbd"], "German");
+    assert_eq!(state.submap, "resize");
+  }
+
+  #[test]
+  fn destroyed_workspaces_leave_no_monitor_behind() {
+    let mut state = initial();
+    apply(
+      &mut state,
+      &[
+        "activespecialv2>>-98,special:scratch,DP-1",
+        "destroyworkspacev2>>-98,special:scratch",
+        "destroyworkspacev2>>1,1",
+      ],
+    );
+    let monitor = &state.monitors["DP-1"];
+    assert_eq!(monitor.active_workspace_id, None);
+    assert_eq!(monitor.special_workspace_id, None);
+
+    apply(&mut state, &["createworkspacev2>>2,2", "workspacev2>>2,2"]);
+    assert_eq!(state.monitors["DP-1"].active_workspace_id, Some(2));
+  }
+
+  #[test]
+  fn replayed_events_change_nothing() {
+    let mut state = initial();
+    let lines = [
+      "openwindow>>2b,1,firefox,Mozilla Firefox",
+      "movewindowv2>>2b,1,1",
+      "closewindow>>1a",
+    ];
+    apply(&mut state, &lines);
+    let once = state.clone();
+    apply(&mut state, &lines);
+    assert_eq!(state, once);
+  }
+
+  #[test]
+  fn replaying_openwindow_keeps_what_was_learned() {
+    let mut state = initial();
+    let client: Client = serde_json::from_str(
+      r#"{ "address": "0x1a", "workspace": { "id": 1, "name": "1" }, "floating": true, "pinned": true }"#,
+    )
+    .unwrap();
+    state.seed_window(&client);
+    let lines = [
+      "openwindow>>1a,1,kitty,~",
+      "windowtitlev2>>1a,vim",
+      "createworkspacev2>>2,2",
+      "movewindowv2>>1a,2,2",
+    ];
+    apply(&mut state, &lines);
+    let once = state.clone();
+    apply(&mut state, &lines);
+    assert_eq!(state, once);
+
+    let kitty = &state.windows[&WindowAddress(0x1a)];
+    assert!(kitty.floating && kitty.pinned);
+    assert_eq!((kitty.title.as_str(), kitty.workspace_id), ("vim", 2));
+  }
+
+  #[test]
+  fn snapshots_stay_consistent() {
+    let state = HyprState::new(initial());
+    let before = state.snapshot();
+    state.apply(&Envelope {
+      seq: 7,
+      received_at: crate::envelope::ReceivedAt::now(),
+      raw: "submap>>resize".to_string(),
+      event: Event::from("submap", "resize"),
+    });
+
+    assert_eq!(before.submap, "");
+    assert_eq!(before.seq, 0);
+    let after = state.snapshot();
+    assert_eq!(after.submap, "resize");
+    assert_eq!(after.seq, 7);
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn connect_queries_then_follows() {
+    crate::runtime::block_on(async {
+      let (hyprland, requests) = crate::dispatch::fake::serve(|request| {
+        match request {
+          "j/clients" => r#"[{ "address": "0x1a", "workspace": { "id": 1, "name": "1" } }]"#,
+          "j/workspaces" => r#"[{ "id": 1, "name": "1", "monitor": "DP-1" }]"#,
+          "j/monitors" => r#"[{ "id": 0, "name": "DP-1", "focused": true }]"#,
+          "j/devices" => r#"{ "keyboards": [] }"#,
+          _ => "{}",
+        }
+        .to_string()
+      });
+      let listener = Listener::new(hyprland.clone());
+      let (state, task) = HyprState::connect(&listener, &Dispatcher::new(hyprland))
+        .await
+        .unwrap();
+      assert_eq!(requests.lock().unwrap().len(), 5);
+      assert!(state.snapshot().windows.contains_key(&WindowAddress(0x1a)));
+
+      listener
+        .listen_on(&b"openwindow>>2b,1,kitty,~\nactivewindowv2>>2b\n"[..])
+        .await;
+      drop(listener);
+      task.await;
+
+      let snapshot = state.snapshot();
+      assert_eq!(snapshot.seq, 2);
+      assert_eq!(snapshot.focused_window().unwrap().class, "kitty");
+    })
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn new_windows_are_seeded_from_clients() {
+    use crate::runtime::sleep;
+    use std::time::Duration;
+
+    crate::runtime::block_on(async {
+      let opened = std::sync::atomic::AtomicBool::new(false);
+      let (hyprland, requests) = crate::dispatch::fake::serve(move |request| {
+        match request {
+          "j/clients" if opened.swap(true, std::sync::atomic::Ordering::SeqCst) => {
+            std::thread::sleep(Duration::from_millis(200));
+            r#"[{ "address": "0x2b", "workspace": { "id": 1, "name": "1" }, "floating": true }]"#
+          }
+          "j/workspaces" => r#"[{ "id": 1, "name": "1", "monitor": "DP-1" }]"#,
+          "j/monitors" => r#"[{ "id": 0, "name": "DP-1", "focused": true }]"#,
+          "j/clients" => "[]",
+          "j/devices" => r#"{ "keyboards": [] }"#,
+          _ => "{}",
+        }
+        .to_string()
+      });
+      let listener = Listener::new(hyprland.clone());
+      let (state, task) = HyprState::connect(&listener, &Dispatcher::new(hyprland))
+        .await
+        .unwrap();
+      let lines = b"openwindow>>2b,1,pavucontrol,Volume\nactivewindowv2>>2b\n";
+      listener.listen_on(&lines[..]).await;
+
+      // The slow query doesn't hold up the events behind `openwindow`.
+      sleep(Duration::from_millis(50)).await;
+      let snapshot = state.snapshot();
+      assert_eq!(snapshot.focused_window, Some(WindowAddress(0x2b)));
+      assert!(!snapshot.windows[&WindowAddress(0x2b)].floating);
+
+      sleep(Duration::from_millis(800)).await;
+      let window = state.snapshot().windows[&WindowAddress(0x2b)].clone();
+      assert!(window.floating);
+      assert_eq!(window.class, "pavucontrol");
+      let queried = requests.lock().unwrap().len();
+      assert_eq!(
+        requests.lock().unwrap().last().map(String::as_str),
+        Some("j/clients")
+      );
+
+      // A window the state knows already isn't queried for again.
+      listener
+        .listen_on(&b"openwindow>>2b,1,pavucontrol,Volume\n"[..])
+        .await;
+      drop(listener);
+      task.await;
+      assert_eq!(requests.lock().unwrap().len(), queried);
+      assert!(state.snapshot().windows[&WindowAddress(0x2b)].floating);
+    })
+  }
+}
@@ -0,0 +1,554 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+#[cfg(feature = "async")]
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+#[cfg(feature = "async")]
+use tracing::{error, info};
+
+use super::{history, HyprState, Monitor, State, Window, Workspace};
+#[cfg(feature = "async")]
+use crate::envelope::Envelope;
+use crate::events::WindowAddress;
+#[cfg(feature = "async")]
+use crate::events::{Event, EventKinds};
+#[cfg(feature = "async")]
+use crate::runtime::{self, Timer};
+#[cfg(feature = "async")]
+use crate::{DispatchError, Dispatcher, Listener, Subscription, Task};
+#[cfg(feature = "async")]
+use futures_channel::mpsc;
+#[cfg(feature = "async")]
+use futures_core::Stream;
+
+/// A value the state had wrong, and what the queries say it is. `None` when the value was
+/// missing on that side.
+///
+/// # Fields
+///
+/// * `before` - The value in the state.
+/// * `after` - The value from the queries, which replaced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+  pub before: Option<T>,
+  pub after: Option<T>,
+}
+
+/// A synthetic event describing a difference between the state kept from events and a fresh
+/// snapshot from the queries.
+///
+/// Every correction means an event was missed or misread, so they are also logged as warnings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Correction {
+  Window(Change<Window>),
+  Workspace(Change<Workspace>),
+  Monitor(Change<Monitor>),
+  FocusedWindow(Change<WindowAddress>),
+  FocusedWorkspace(Change<i32>),
+  FocusedMonitor(Change<String>),
+}
+
+fn diff_map<K, V, F>(before: &BTreeMap<K, V>, after: &BTreeMap<K, V>, wrap: F) -> Vec<Correction>
+where
+  K: Ord,
+  V: Clone + PartialEq,
+  F: Fn(Change<V>) -> Correction,
+{
+  let removed = before
+    .iter()
+    .filter(|(key, _)| !after.contains_key(key))
+    .map(|(_, value)| Change {
+      before: Some(value.clone()),
+      after: None,
+    });
+  let changed = after
+    .iter()
+    .filter(|(key, value)| before.get(key) != Some(value))
+    .map(|(key, value)| Change {
+      before: before.get(key).cloned(),
+      after: Some(value.clone()),
+    });
+  removed.chain(changed).map(wrap).collect()
+}
+
+fn diff_value<V, F>(before: &Option<V>, after: &Option<V>, wrap: F) -> Option<Correction>
+where
+  V: Clone + PartialEq,
+  F: Fn(Change<V>) -> Correction,
+{
+  (before != after).then(|| {
+    wrap(Change {
+      before: before.clone(),
+      after: after.clone(),
+    })
+  })
+}
+
+impl State {
+  /// Replaces the windows, workspaces, monitors and focus with those of `fresh`, a state built
+  /// from queries, and returns what differed. The submap and keyboard layouts are kept, as no
+  /// query reports the submap and events report layouts reliably. The focus history keeps its
+  /// order, without the windows that are gone and with the missing ones added at the end.
+  pub fn reconcile(&mut self, fresh: State) -> Vec<Correction> {
+    let mut corrections = diff_map(&self.windows, &fresh.windows, Correction::Window);
+    corrections.extend(diff_map(
+      &self.workspaces,
+      &fresh.workspaces,
+      Correction::Workspace,
+    ));
+    corrections.extend(diff_map(
+      &self.monitors,
+      &fresh.monitors,
+      Correction::Monitor,
+    ));
+    corrections.extend(diff_value(
+      &self.focused_window,
+      &fresh.focused_window,
+      Correction::FocusedWindow,
+    ));
+    corrections.extend(diff_value(
+      &self.focused_workspace,
+      &fresh.focused_workspace,
+      Correction::FocusedWorkspace,
+    ));
+    corrections.extend(diff_value(
+      &self.focused_monitor,
+      &fresh.focused_monitor,
+      Correction::FocusedMonitor,
+    ));
+
+    self
+      .focus_history
+      .retain(|address| fresh.windows.contains_key(address));
+    for address in fresh.focus_history {
+      history::opened(&mut self.focus_history, address);
+    }
+    self.windows = fresh.windows;
+    self.workspaces = fresh.workspaces;
+    self.monitors = fresh.monitors;
+    self.focused_window = fresh.focused_window;
+    self.focused_workspace = fresh.focused_workspace;
+    self.focused_monitor = fresh.focused_monitor;
+    corrections
+  }
+}
+
+impl HyprState {
+  /// Reconciles the shared state with `fresh`, see [`State::reconcile`], and logs every
+  /// correction.
+  pub fn reconcile(&self, fresh: State) -> Vec<Correction> {
+    let (seq, corrections) = self.update(|state| (state.seq, state.reconcile(fresh)));
+    for correction in &corrections {
+      warn!(seq = seq, correction = ?correction, "State drifted from the compositor");
+    }
+    corrections
+  }
+
+  /// Like [`reconcile`](HyprState::reconcile), unless the state moved from `mark`, taken before
+  /// querying `fresh`: a line was read since, which `fresh` may or may not reflect, or an envelope
+  /// is still waiting to be applied. Nothing changes then.
+  #[cfg(feature = "async")]
+  fn reconcile_at(&self, mark: u64, fresh: State) -> Option<Vec<Correction>> {
+    let corrections = self.update_at(mark, |state| state.reconcile(fresh))?;
+    for correction in &corrections {
+      warn!(seq = mark, correction = ?correction, "State drifted from the compositor");
+    }
+    Some(corrections)
+  }
+}
+
+/// How long a reconciliation triggered by a suspicious envelope waits, so that a burst of them
+/// leads to a single round of queries.
+#[cfg(feature = "async")]
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// How many rounds of queries [`Reconciler::reconcile`] runs before giving up on a state that keeps
+/// changing under them.
+#[cfg(feature = "async")]
+const ATTEMPTS: usize = 3;
+
+/// Re-queries the compositor to correct a [`HyprState`] that missed events.
+///
+/// Once [spawned](Reconciler::spawn), it reconciles on an interval, and shortly after the
+/// listener [lost](Subscription::lost) lines, such as a line that couldn't be interpreted, or
+/// read an event this crate has no model for. Replies are thrown away and the queries run again
+/// when a line was read while they ran, or when the state hadn't applied every event read before.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use hyprlib::state::{HyprState, Reconciler};
+/// use hyprlib::{Dispatcher, Hyprland, Listener};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let listener = Listener::new(Hyprland::default());
+/// let dispatcher = Dispatcher::new(Hyprland::default());
+/// let (state, _task) = HyprState::connect(&listener, &dispatcher).await?;
+/// let (mut corrections, _reconciler) = Reconciler::new(state, dispatcher)
+///   .interval(Some(Duration::from_secs(300)))
+///   .spawn(&listener);
+/// listener.listen().await?;
+///
+/// while let Some(correction) = corrections.recv().await {
+///   eprintln!("missed an event: {:?}", correction);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub struct Reconciler {
+  state: HyprState,
+  dispatcher: Dispatcher,
+  interval: Option<Duration>,
+  on_gaps: bool,
+}
+
+#[cfg(feature = "async")]
+impl Reconciler {
+  /// A reconciler running every minute and after gaps.
+  pub fn new(state: HyprState, dispatcher: Dispatcher) -> Self {
+    Reconciler {
+      state,
+      dispatcher,
+      interval: Some(Duration::from_secs(60)),
+      on_gaps: true,
+    }
+  }
+
+  /// How often to reconcile, `None` to only reconcile after gaps.
+  pub fn interval(mut self, interval: Option<Duration>) -> Self {
+    self.interval = interval;
+    self
+  }
+
+  /// Whether to reconcile after gaps and unknown events.
+  pub fn on_gaps(mut self, on_gaps: bool) -> Self {
+    self.on_gaps = on_gaps;
+    self
+  }
+
+  /// Queries the compositor and corrects the state, for example after reconnecting. The queries
+  /// run again when events are applied meanwhile, and after a few tries the state is left as is
+  /// and no correction is returned.
+  pub async fn reconcile(&self) -> Result<Vec<Correction>, DispatchError> {
+    for _ in 0..ATTEMPTS {
+      let mark = self.state.mark();
+      let fresh = self.dispatcher.state().await?;
+      if let Some(corrections) = mark.and_then(|mark| self.state.reconcile_at(mark, fresh)) {
+        return Ok(corrections);
+      }
+    }
+    info!(
+      attempts = ATTEMPTS,
+      "State kept changing during the queries, skipping reconciliation"
+    );
+    Ok(Vec::new())
+  }
+
+  /// Spawns the reconciliation loop, watching `listener` for gaps. The loop ends, closing the
+  /// returned [`Corrections`], once `listener` is dropped and its reading task has finished.
+  pub fn spawn(self, listener: &Listener) -> (Corrections, Task) {
+    let (sender, receiver) = mpsc::unbounded();
+    let mut subscription = listener.subscribe();
+    if !self.on_gaps {
+      // Only watch for the listener going away, without parsing anything for it.
+      subscription = subscription.only(EventKinds::NONE);
+    }
+    let task = runtime::spawn(async move {
+      let mut due = self.interval.map(|interval| Instant::now() + interval);
+      let mut timer = due.map(Timer::at);
+      let mut lost = 0;
+      loop {
+        let envelope = match next_wake(&mut subscription, timer.as_mut()).await {
+          Wake::Envelope(Some(envelope)) => envelope,
+          Wake::Envelope(None) => break,
+          Wake::Due => {
+            match self.reconcile().await {
+              Ok(corrections) => {
+                info!(corrections = corrections.len(), "State reconciled");
+                for correction in corrections {
+                  let _ = sender.unbounded_send(correction);
+                }
+              }
+              Err(e) => error!("Reconciliation failed: {}", e),
+            }
+            due = self.interval.map(|interval| Instant::now() + interval);
+            timer = due.map(Timer::at);
+            continue;
+          }
+        };
+
+        // Sequence numbers skip filtered lines as well, only lost ones count as a gap.
+        let now = subscription.lost();
+        let gap = now - lost;
+        lost = now;
+        if !self.on_gaps || (gap == 0 && !is_unknown(&envelope)) {
+          continue;
+        }
+        info!(
+          seq = envelope.seq,
+          gap = gap,
+          "Suspicious envelope, reconciling soon"
+        );
+        let at = Instant::now() + SETTLE;
+        if due.is_none_or(|due| at < due) {
+          due = Some(at);
+          match timer.as_mut() {
+            Some(timer) => timer.reset(at),
+            None => timer = Some(Timer::at(at)),
+          }
+        }
+      }
+    });
+    (Corrections { receiver }, task)
+  }
+}
+
+#[cfg(feature = "async")]
+fn is_unknown(envelope: &Envelope) -> bool {
+  matches!(envelope.event, Event::Unknown { .. })
+}
+
+#[cfg(feature = "async")]
+enum Wake {
+  Due,
+  Envelope(Option<Envelope>),
+}
+
+/// Waits for the next envelope or for `timer` to expire, whichever comes first.
+#[cfg(feature = "async")]
+async fn next_wake(subscription: &mut Subscription, mut timer: Option<&mut Timer>) -> Wake {
+  std::future::poll_fn(|cx| {
+    if let Some(timer) = timer.as_mut() {
+      if timer.poll_expired(cx).is_ready() {
+        return Poll::Ready(Wake::Due);
+      }
+    }
+    Pin::new(&mut *subscription)
+      .poll_next(cx)
+      .map(Wake::Envelope)
+  })
+  .await
+}
+
+/// The corrections found by a spawned [`Reconciler`].
+#[cfg(feature = "async")]
+pub struct Corrections {
+  receiver: mpsc::UnboundedReceiver<Correction>,
+}
+
+#[cfg(feature = "async")]
+impl Corrections {
+  /// Waits for the next correction. Returns `None` once the reconciler has stopped.
+  pub async fn recv(&mut self) -> Option<Correction> {
+    std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+  }
+}
+
+#[cfg(feature = "async")]
+impl Stream for Corrections {
+  type Item = Correction;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Correction>> {
+    Pin::new(&mut self.receiver).poll_next(cx)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::events::Event;
+
+  fn window(address: u64, workspace_id: i32) -> Window {
+    Window {
+      address: WindowAddress(address),
+      workspace_id,
+      class: "kitty".to_string(),
+      title: "~".to_string(),
+      floating: false,
+      pinned: false,
+      fullscreen: false,
+    }
+  }
+
+  #[test]
+  fn reconcile_reports_and_fixes_drift() {
+    let mut state = State::default();
+    for (address, workspace_id) in [(0x1a, 1), (0x2b, 1)] {
+      state
+        .windows
+        .insert(WindowAddress(address), window(address, workspace_id));
+    }
+    state.focused_window = Some(WindowAddress(0x2b));
+    state.apply(&Event::from("submap", "resize"));
+
+    let mut fresh = State::default();
+    for (address, workspace_id) in [(0x1a, 2), (0x3c, 1)] {
+      fresh
+        .windows
+        .insert(WindowAddress(address), window(address, workspace_id));
+    }
+    fresh.focused_window = Some(WindowAddress(0x3c));
+
+    let corrections = state.reconcile(fresh.clone());
+    assert_eq!(
+      corrections,
+      [
+        Correction::Window(Change {
+          before: Some(window(0x2b, 1)),
+          after: None,
+        }),
+        Correction::Window(Change {
+          before: Some(window(0x1a, 1)),
+          after: Some(window(0x1a, 2)),
+        }),
+        Correction::Window(Change {
+          before: None,
+          after: Some(window(0x3c, 1)),
+        }),
+        Correction::FocusedWindow(Change {
+          before: Some(WindowAddress(0x2b)),
+          after: Some(WindowAddress(0x3c)),
+        }),
+      ]
+    );
+    assert_eq!(state.windows, fresh.windows);
+    assert_eq!(state.submap, "resize");
+    assert!(state.reconcile(fresh).is_empty());
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn gaps_trigger_reconciliation() {
+    runtime::block_on(async {
+      let (hyprland, requests) = crate::dispatch::fake::serve(|request| {
+        match request {
+          "j/clients" => r#"[{ "address": "0x1a", "workspace": { "id": 1, "name": "1" } }]"#,
+          "j/devices" => r#"{ "keyboards": [] }"#,
+          "j/activewindow" => "{}",
+          _ => "[]",
+        }
+        .to_string()
+      });
+      let listener = Listener::new(hyprland.clone());
+      let state = HyprState::default();
+      let (mut corrections, task) = Reconciler::new(state.clone(), Dispatcher::new(hyprland))
+        .interval(None)
+        .spawn(&listener);
+
+      // The second line is invalid, so its sequence number never reaches subscribers.
+      listener
+        .listen_on(&b"workspace>>1\nopenwindow>>garbage\nworkspace>>1\n"[..])
+        .await;
+      assert_eq!(
+        corrections.recv().await,
+        Some(Correction::Window(Change {
+          before: None,
+          after: state.snapshot().windows.get(&WindowAddress(0x1a)).cloned(),
+        }))
+      );
+      assert_eq!(requests.lock().unwrap().len(), 5);
+
+      drop(listener);
+      task.await;
+      assert_eq!(corrections.recv().await, None);
+    })
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn events_during_the_queries_start_them_over() {
+    use crate::envelope::ReceivedAt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    runtime::block_on(async {
+      let state = HyprState::default();
+      let applied = state.clone();
+      let rounds = AtomicUsize::new(0);
+      let (hyprland, requests) = crate::dispatch::fake::serve(move |request| {
+        match request {
+          "j/clients" if rounds.fetch_add(1, Ordering::SeqCst) == 0 => {
+            r#"[
+              { "address": "0x1a", "workspace": { "id": 1, "name": "1" } },
+              { "address": "0x2b", "workspace": { "id": 1, "name": "1" } }
+            ]"#
+          }
+          "j/clients" => r#"[{ "address": "0x1a", "workspace": { "id": 1, "name": "1" } }]"#,
+          "j/devices" => {
+            // Closes 0x2b after the first reply listed it.
+            if applied.snapshot().seq == 0 {
+              applied.apply(&Envelope {
+                seq: 1,
+                received_at: ReceivedAt::now(),
+                raw: "closewindow>>2b".to_string(),
+                event: Event::from("closewindow", "2b"),
+              });
+            }
+            r#"{ "keyboards": [] }"#
+          }
+          "j/activewindow" => "{}",
+          _ => "[]",
+        }
+        .to_string()
+      });
+      let reconciler = Reconciler::new(state.clone(), Dispatcher::new(hyprland));
+
+      let corrections = reconciler.reconcile().await.unwrap();
+      let snapshot = state.snapshot();
+      assert_eq!(
+        corrections,
+        [Correction::Window(Change {
+          before: None,
+          after: snapshot.windows.get(&WindowAddress(0x1a)).cloned(),
+        })]
+      );
+      assert!(!snapshot.windows.contains_key(&WindowAddress(0x2b)));
+      assert_eq!(requests.lock().unwrap().len(), 10);
+    })
+  }
+
+  #[cfg(feature = "async")]
+  #[test]
+  fn events_read_but_not_applied_are_no_drift() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    runtime::block_on(async {
+      let closed = AtomicBool::new(false);
+      let (hyprland, requests) = crate::dispatch::fake::serve(move |request| {
+        match request {
+          "j/clients" if !closed.swap(true, Ordering::SeqCst) => {
+            r#"[
+              { "address": "0x1a", "workspace": { "id": 1, "name": "1" } },
+              { "address": "0x2b", "workspace": { "id": 1, "name": "1" } }
+            ]"#
+          }
+          "j/clients" => r#"[{ "address": "0x1a", "workspace": { "id": 1, "name": "1" } }]"#,
+          "j/devices" => r#"{ "keyboards": [] }"#,
+          "j/activewindow" => "{}",
+          _ => "[]",
+        }
+        .to_string()
+      });
+      let dispatcher = Dispatcher::new(hyprland.clone());
+      let listener = Listener::new(hyprland);
+      let mut subscription = listener.subscribe().only(State::KINDS);
+      let state = HyprState::new(dispatcher.state().await.unwrap());
+      // Followed, but nothing applies the envelopes until the test does.
+      state.shared.lock().unwrap().cursor = Some(subscription.cursor());
+      listener.listen_on(&b"closewindow>>2b\n"[..]).await;
+
+      let reconciler = Reconciler::new(state.clone(), dispatcher);
+      assert!(reconciler.reconcile().await.unwrap().is_empty());
+      assert!(state.snapshot().windows.contains_key(&WindowAddress(0x2b)));
+      assert_eq!(requests.lock().unwrap().len(), 5 + 5 * ATTEMPTS);
+
+      state.apply(&subscription.recv().await.unwrap());
+      assert!(reconciler.reconcile().await.unwrap().is_empty());
+      assert_eq!(requests.lock().unwrap().len(), 10 + 5 * ATTEMPTS);
+    })
+  }
+}
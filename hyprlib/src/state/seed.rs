@@ -0,0 +1,92 @@
+//! Seeds new windows with what `openwindow` doesn't tell, see [`State::seed_window`].
+//!
+//! The `clients` query runs on its own task, so a slow request socket never holds up the events.
+//! Windows opened while a query is out wait for the next one, which covers all of them.
+
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+use tracing::warn;
+
+use super::{HyprState, State};
+use crate::dispatch::until;
+use crate::events::WindowAddress;
+use crate::runtime::{self, Task};
+use crate::Dispatcher;
+
+/// How long one `clients` query may take.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How many queries run for a batch whose reply keeps arriving after newer events.
+const ATTEMPTS: usize = 3;
+
+/// Hands the addresses of newly opened windows to the seeding task.
+pub(super) struct Seeder {
+  sender: mpsc::UnboundedSender<WindowAddress>,
+  task: Task,
+}
+
+impl Seeder {
+  pub(super) fn spawn(state: HyprState, dispatcher: Dispatcher) -> Self {
+    let (sender, receiver) = mpsc::unbounded();
+    let task = runtime::spawn(run(state, dispatcher, receiver));
+    Seeder { sender, task }
+  }
+
+  pub(super) fn seed(&self, address: WindowAddress) {
+    let _ = self.sender.unbounded_send(address);
+  }
+
+  /// Waits for the windows handed over so far to be seeded.
+  pub(super) async fn finish(self) {
+    drop(self.sender);
+    self.task.await;
+  }
+}
+
+async fn run(
+  state: HyprState,
+  dispatcher: Dispatcher,
+  mut receiver: mpsc::UnboundedReceiver<WindowAddress>,
+) {
+  while let Some(address) = std::future::poll_fn(|cx| Pin::new(&mut receiver).poll_next(cx)).await {
+    let mut pending = HashSet::from([address]);
+    while let Ok(address) = receiver.try_recv() {
+      pending.insert(address);
+    }
+    seed(&state, &dispatcher, &pending).await;
+  }
+}
+
+/// Queries `clients` once for every window in `pending`. A reply is only used when the state
+/// didn't move while it was on its way, as it could undo a newer `changefloatingmode` or `pin`.
+async fn seed(state: &HyprState, dispatcher: &Dispatcher, pending: &HashSet<WindowAddress>) {
+  for _ in 0..ATTEMPTS {
+    let mark = state.mark();
+    let clients = match until(Instant::now() + TIMEOUT, dispatcher.clients()).await {
+      Ok(clients) => clients,
+      Err(e) => {
+        warn!("Failed to query {} new windows: {}", pending.len(), e);
+        return;
+      }
+    };
+    let seed = |state: &mut State| {
+      for client in clients
+        .iter()
+        .filter(|client| pending.contains(&client.address))
+      {
+        state.seed_window(client);
+      }
+    };
+    if mark.and_then(|mark| state.update_at(mark, seed)).is_some() {
+      return;
+    }
+  }
+  warn!(
+    "The state kept changing, {} new windows not seeded",
+    pending.len()
+  );
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::fmt;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[cfg(feature = "async")]
+use futures_core::Stream;
+use thiserror::Error;
+
+use super::{HyprState, State, Window, Workspace};
+
+/// Returned by [`Watch::changed`] once the [`HyprState`] behind it was
+/// [closed](HyprState::close) and every change was seen.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("The state is no longer updated")]
+pub struct Closed;
+
+type Derive<T> = Box<dyn Fn(&State) -> T + Send + Sync>;
+
+/// A value derived from a [`HyprState`], in the spirit of a watch channel.
+///
+/// The value is derived again after every update of the state, but [`changed`](Watch::changed)
+/// only returns, and the stream only yields, when it differs from the last one seen. A consumer
+/// that only cares about the focused window therefore doesn't wake for title changes elsewhere.
+///
+/// ```no_run
+/// # async fn run(state: hyprlib::state::HyprState) {
+/// let mut submap = state.submap();
+/// while submap.changed().await.is_ok() {
+///   println!("submap: {}", submap.get());
+/// }
+/// # }
+/// ```
+pub struct Watch<T> {
+  state: HyprState,
+  derive: Derive<T>,
+  value: T,
+  version: u64,
+}
+
+impl<T: PartialEq> Watch<T> {
+  /// The value as of creation or the last [`changed`](Watch::changed).
+  pub fn get(&self) -> &T {
+    &self.value
+  }
+
+  /// Waits until the derived value differs from the one last seen, and makes it the current one.
+  pub async fn changed(&mut self) -> Result<(), Closed> {
+    std::future::poll_fn(|cx| self.poll_changed(cx)).await
+  }
+
+  fn poll_changed(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Closed>> {
+    loop {
+      let state = {
+        let mut shared = self.state.shared.lock().unwrap();
+        if shared.version == self.version {
+          if shared.closed {
+            return Poll::Ready(Err(Closed));
+          }
+          if !shared
+            .wakers
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+          {
+            shared.wakers.push(cx.waker().clone());
+          }
+          return Poll::Pending;
+        }
+        self.version = shared.version;
+        shared.state.clone()
+      };
+      let value = (self.derive)(&state);
+      if value != self.value {
+        self.value = value;
+        return Poll::Ready(Ok(()));
+      }
+    }
+  }
+}
+
+/// Yields every new value, ending once the state was closed.
+#[cfg(feature = "async")]
+impl<T: PartialEq + Clone + Unpin> Stream for Watch<T> {
+  type Item = T;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+    self
+      .poll_changed(cx)
+      .map(|changed| changed.ok().map(|()| self.value.clone()))
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Watch<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Watch")
+      .field("value", &self.value)
+      .field("version", &self.version)
+      .finish()
+  }
+}
+
+impl HyprState {
+  /// Watches the value `derive` computes from the state. It should be cheap, as it runs after
+  /// every update.
+  ///
+  /// ```no_run
+  /// # fn run(state: hyprlib::state::HyprState) {
+  /// let windows_on_web = state.watch(|state| {
+  ///   let web = state.workspace_by_name("web").map(|workspace| workspace.id);
+  ///   web.map_or(0, |id| state.windows_on(id).count())
+  /// });
+  /// # }
+  /// ```
+  pub fn watch<T, F>(&self, derive: F) -> Watch<T>
+  where
+    F: Fn(&State) -> T + Send + Sync + 'static,
+  {
+    let (state, version) = {
+      let shared = self.shared.lock().unwrap();
+      (shared.state.clone(), shared.version)
+    };
+    Watch {
+      state: self.clone(),
+      value: derive(&state),
+      derive: Box::new(derive),
+      version,
+    }
+  }
+
+  /// The focused window.
+  pub fn focused_window(&self) -> Watch<Option<Window>> {
+    self.watch(|state| state.focused_window().cloned())
+  }
+
+  /// The workspace shown on the monitor named `monitor`.
+  pub fn active_workspace(&self, monitor: &str) -> Watch<Option<Workspace>> {
+    let monitor = monitor.to_string();
+    self.watch(move |state| {
+      let id = state.monitors.get(&monitor)?.active_workspace_id?;
+      state.workspaces.get(&id).cloned()
+    })
+  }
+
+  /// The ID of the workspace shown on every monitor, by monitor name.
+  pub fn visible_workspaces(&self) -> Watch<BTreeMap<String, i32>> {
+    self.watch(|state| {
+      state
+        .monitors
+        .values()
+        .filter_map(|monitor| Some((monitor.name.clone(), monitor.active_workspace_id?)))
+        .collect()
+    })
+  }
+
+  /// The active keybind submap, empty for the default one.
+  pub fn submap(&self) -> Watch<String> {
+    self.watch(|state| state.submap.clone())
+  }
+
+  /// The active layout of the keyboard named `keyboard`.
+  pub fn keyboard_layout(&self, keyboard: &str) -> Watch<Option<String>> {
+    let keyboard = keyboard.to_string();
+    self.watch(move |state| state.keyboard_layouts.get(&keyboard).cloned())
+  }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+  use super::*;
+  use crate::envelope::{Envelope, ReceivedAt};
+  use crate::events::Event;
+  use crate::runtime::block_on;
+  use futures_util::StreamExt;
+
+  fn apply(state: &HyprState, line: &str) {
+    let (name, data) = line.split_once(">>").unwrap();
+    state.apply(&Envelope {
+      seq: 0,
+      received_at: ReceivedAt::now(),
+      raw: line.to_string(),
+      event: Event::from(name, data),
+    });
+  }
+
+  #[test]
+  fn watches_skip_updates_that_keep_the_value() {
+    block_on(async {
+      let state = HyprState::default();
+      let mut submap = state.submap();
+      let mut layout = state.keyboard_layout("kbd");
+      assert_eq!(submap.get(), "");
+
+      apply(&state, "activelayout>>kbd,German");
+      apply(&state, "submap>>resize");
+      apply(&state, "activelayout>>other,English (US)");
+      apply(&state, "submap>>resize");
+      state.close();
+
+      submap.changed().await.unwrap();
+      assert_eq!(submap.get(), "resize");
+      assert_eq!(submap.changed().await, Err(Closed));
+      assert_eq!(
+        layout.by_ref().collect::<Vec<_>>().await,
+        [Some("German".to_string())]
+      );
+    })
+  }
+
+  #[test]
+  fn changed_wakes_on_later_updates() {
+    block_on(async {
+      let state = HyprState::default();
+      let mut workspace = state.active_workspace("DP-1");
+      let writer = state.clone();
+      let task = crate::runtime::spawn(async move {
+        crate::runtime::sleep(std::time::Duration::from_millis(10)).await;
+        apply(&writer, "monitoraddedv2>>0,DP-1,");
+        apply(&writer, "createworkspacev2>>4,4");
+        apply(&writer, "focusedmon>>DP-1,4");
+      });
+
+      workspace.changed().await.unwrap();
+      assert_eq!(workspace.get().as_ref().unwrap().id, 4);
+      task.await;
+    })
+  }
+}
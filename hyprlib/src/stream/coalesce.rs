@@ -0,0 +1,372 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+
+use super::Deadline;
+use crate::envelope::Envelope;
+use crate::events::{Event, WindowAddress};
+
+/// A logical event produced by [`EventStreamExt::coalesce`](super::EventStreamExt::coalesce).
+/// Each merged variant is built from a v1/v2 pair. Fields only carried by one half are `None` when
+/// that half never arrived.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Coalesced {
+  /// `workspace` + `workspacev2`.
+  WorkspaceChanged { id: Option<i32>, name: String },
+  /// `activewindow` + `activewindowv2`. When no window is focused `address` is `None` and the
+  /// class and title are empty strings.
+  ActiveWindowChanged {
+    address: Option<WindowAddress>,
+    class: Option<String>,
+    title: Option<String>,
+  },
+  /// `createworkspace` + `createworkspacev2`.
+  WorkspaceCreated { id: Option<i32>, name: String },
+  /// `movewindow` + `movewindowv2`.
+  WindowMoved {
+    address: WindowAddress,
+    workspace_id: Option<i32>,
+    workspace_name: String,
+  },
+  /// Any event that is not part of a pair, passed through unchanged.
+  Other(Event),
+}
+
+impl Coalesced {
+  /// Builds the logical event for a half whose partner never arrived.
+  fn from_half(event: Event) -> Self {
+    match event {
+      Event::Workspace(e) => Coalesced::WorkspaceChanged {
+        id: None,
+        name: e.workspace_name,
+      },
+      Event::WorkspaceV2(e) => Coalesced::WorkspaceChanged {
+        id: Some(e.workspace_id),
+        name: e.workspace_name,
+      },
+      Event::ActiveWindow(e) => Coalesced::ActiveWindowChanged {
+        address: None,
+        class: Some(e.window_class),
+        title: Some(e.window_title),
+      },
+      Event::ActiveWindowV2(e) => Coalesced::ActiveWindowChanged {
+        address: e.window_address,
+        class: None,
+        title: None,
+      },
+      Event::CreateWorkspace(e) => Coalesced::WorkspaceCreated {
+        id: None,
+        name: e.workspace_name,
+      },
+      Event::CreateWorkspaceV2(e) => Coalesced::WorkspaceCreated {
+        id: Some(e.workspace_id),
+        name: e.workspace_name,
+      },
+      Event::MoveWindow(e) => Coalesced::WindowMoved {
+        address: e.window_address,
+        workspace_id: None,
+        workspace_name: e.workspace_name,
+      },
+      Event::MoveWindowV2(e) => Coalesced::WindowMoved {
+        address: e.window_address,
+        workspace_id: Some(e.workspace_id),
+        workspace_name: e.workspace_name,
+      },
+      other => Coalesced::Other(other),
+    }
+  }
+
+  /// Merges two halves of the same pair, in either order. Returns `None` when they don't belong
+  /// together: the workspace and window pairs have to name the same workspace or window, and the
+  /// `activewindow` pair, which shares nothing to compare, has to be read back to back and agree
+  /// on whether a window is focused.
+  fn merge(first: &Envelope, second: &Envelope) -> Option<Self> {
+    let adjacent = second.seq == first.seq + 1;
+    let merged = match (&first.event, &second.event) {
+      (Event::Workspace(v1), Event::WorkspaceV2(v2))
+      | (Event::WorkspaceV2(v2), Event::Workspace(v1))
+        if v1.workspace_name == v2.workspace_name =>
+      {
+        Coalesced::WorkspaceChanged {
+          id: Some(v2.workspace_id),
+          name: v2.workspace_name.clone(),
+        }
+      }
+      (Event::ActiveWindow(v1), Event::ActiveWindowV2(v2))
+      | (Event::ActiveWindowV2(v2), Event::ActiveWindow(v1))
+        if adjacent
+          && (v1.window_class.is_empty() && v1.window_title.is_empty())
+            == v2.window_address.is_none() =>
+      {
+        Coalesced::ActiveWindowChanged {
+          address: v2.window_address,
+          class: Some(v1.window_class.clone()),
+          title: Some(v1.window_title.clone()),
+        }
+      }
+      (Event::CreateWorkspace(v1), Event::CreateWorkspaceV2(v2))
+      | (Event::CreateWorkspaceV2(v2), Event::CreateWorkspace(v1))
+        if v1.workspace_name == v2.workspace_name =>
+      {
+        Coalesced::WorkspaceCreated {
+          id: Some(v2.workspace_id),
+          name: v2.workspace_name.clone(),
+        }
+      }
+      (Event::MoveWindow(v1), Event::MoveWindowV2(v2))
+      | (Event::MoveWindowV2(v2), Event::MoveWindow(v1))
+        if v1.window_address == v2.window_address =>
+      {
+        Coalesced::WindowMoved {
+          address: v2.window_address,
+          workspace_id: Some(v2.workspace_id),
+          workspace_name: v2.workspace_name.clone(),
+        }
+      }
+      _ => return None,
+    };
+    Some(merged)
+  }
+}
+
+fn is_half(event: &Event) -> bool {
+  matches!(
+    event,
+    Event::Workspace(_)
+      | Event::WorkspaceV2(_)
+      | Event::ActiveWindow(_)
+      | Event::ActiveWindowV2(_)
+      | Event::CreateWorkspace(_)
+      | Event::CreateWorkspaceV2(_)
+      | Event::MoveWindow(_)
+      | Event::MoveWindowV2(_)
+  )
+}
+
+/// Stream returned by [`EventStreamExt::coalesce`](super::EventStreamExt::coalesce).
+///
+/// A merged envelope keeps the `seq` and `received_at` of the first half, and its `raw` holds
+/// both lines separated by a newline.
+pub struct Coalesce<S> {
+  inner: S,
+  timeout: Duration,
+  pending: Option<Envelope>,
+  deadline: Deadline,
+  ready: VecDeque<Envelope<Coalesced>>,
+  done: bool,
+}
+
+impl<S> Coalesce<S> {
+  pub(crate) fn new(inner: S, timeout: Duration) -> Self {
+    Coalesce {
+      inner,
+      timeout,
+      pending: None,
+      deadline: Deadline::new(),
+      ready: VecDeque::new(),
+      done: false,
+    }
+  }
+
+  fn flush_pending(&mut self) {
+    self.deadline.clear();
+    if let Some(pending) = self.pending.take() {
+      self.ready.push_back(pending.map(Coalesced::from_half));
+    }
+  }
+
+  fn push(&mut self, envelope: Envelope) {
+    if let Some(pending) = self.pending.take() {
+      if let Some(merged) = Coalesced::merge(&pending, &envelope) {
+        self.deadline.clear();
+        self.ready.push_back(Envelope {
+          seq: pending.seq,
+          received_at: pending.received_at,
+          raw: format!("{}\n{}", pending.raw, envelope.raw),
+          event: merged,
+        });
+        return;
+      }
+      self.pending = Some(pending);
+      self.flush_pending();
+    }
+
+    if is_half(&envelope.event) {
+      self.pending = Some(envelope);
+      self.deadline.set(Instant::now() + self.timeout);
+    } else {
+      self.ready.push_back(envelope.map(Coalesced::Other));
+    }
+  }
+}
+
+impl<S> Stream for Coalesce<S>
+where
+  S: Stream<Item = Envelope> + Unpin,
+{
+  type Item = Envelope<Coalesced>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = &mut *self;
+    loop {
+      if let Some(envelope) = this.ready.pop_front() {
+        return Poll::Ready(Some(envelope));
+      }
+      if this.done {
+        this.flush_pending();
+        return Poll::Ready(this.ready.pop_front());
+      }
+
+      match Pin::new(&mut this.inner).poll_next(cx) {
+        Poll::Ready(Some(envelope)) => this.push(envelope),
+        Poll::Ready(None) => this.done = true,
+        Poll::Pending => {
+          if !this.deadline.poll_expired(cx) {
+            return Poll::Pending;
+          }
+          this.flush_pending();
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::envelope::ReceivedAt;
+  use crate::runtime::block_on;
+  use crate::stream::EventStreamExt;
+  use futures_util::{stream, StreamExt};
+  use hyprstream_proto::interpreter::Interpreter;
+
+  fn envelopes(lines: &[&str]) -> Vec<Envelope> {
+    lines
+      .iter()
+      .enumerate()
+      .map(|(i, line)| Envelope {
+        seq: i as u64 + 1,
+        received_at: ReceivedAt::now(),
+        raw: line.to_string(),
+        event: Interpreter::new(line).interpret().unwrap(),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn merges_pairs_and_passes_others_through() {
+    block_on(async {
+      let input = envelopes(&[
+        "workspace>>2",
+        "workspacev2>>2,2",
+        "activewindow>>kitty,~",
+        "activewindowv2>>55d5a8b2c9a0",
+        "submap>>resize",
+      ]);
+      let output: Vec<_> = stream::iter(input)
+        .coalesce(Duration::from_millis(50))
+        .collect()
+        .await;
+
+      assert_eq!(output.len(), 3);
+      assert_eq!(output[0].seq, 1);
+      assert_eq!(output[0].raw, "workspace>>2\nworkspacev2>>2,2");
+      assert_eq!(
+        output[0].event,
+        Coalesced::WorkspaceChanged {
+          id: Some(2),
+          name: "2".to_string()
+        }
+      );
+      assert_eq!(
+        output[1].event,
+        Coalesced::ActiveWindowChanged {
+          address: Some(WindowAddress(0x55d5a8b2c9a0)),
+          class: Some("kitty".to_string()),
+          title: Some("~".to_string()),
+        }
+      );
+      assert!(matches!(
+        output[2].event,
+        Coalesced::Other(Event::Submap(_))
+      ));
+    })
+  }
+
+  #[test]
+  fn emits_lone_half_after_timeout() {
+    block_on(async {
+      let (sender, receiver) = futures_channel::mpsc::unbounded();
+      let mut coalesced = receiver.coalesce(Duration::from_millis(20));
+
+      for envelope in envelopes(&["createworkspace>>web"]) {
+        sender.unbounded_send(envelope).unwrap();
+      }
+      let lone = coalesced.next().await.unwrap();
+      assert_eq!(
+        lone.event,
+        Coalesced::WorkspaceCreated {
+          id: None,
+          name: "web".to_string()
+        }
+      );
+    })
+  }
+
+  #[test]
+  fn keeps_unrelated_halves_apart() {
+    block_on(async {
+      let input = envelopes(&[
+        "workspace>>2",
+        "workspacev2>>3,3",
+        "createworkspace>>web",
+        "createworkspacev2>>4,mail",
+        "activewindow>>kitty,~",
+        "submap>>resize",
+        "activewindowv2>>55d5a8b2c9a0",
+        "activewindow>>,",
+        "activewindowv2>>55d5a8b2c9a0",
+      ]);
+      let output: Vec<_> = stream::iter(input)
+        .coalesce(Duration::from_millis(50))
+        .collect()
+        .await;
+
+      let events: Vec<_> = output.into_iter().map(|envelope| envelope.event).collect();
+      assert_eq!(
+        events[..4],
+        [
+          Coalesced::WorkspaceChanged {
+            id: None,
+            name: "2".to_string()
+          },
+          Coalesced::WorkspaceChanged {
+            id: Some(3),
+            name: "3".to_string()
+          },
+          Coalesced::WorkspaceCreated {
+            id: None,
+            name: "web".to_string()
+          },
+          Coalesced::WorkspaceCreated {
+            id: Some(4),
+            name: "mail".to_string()
+          },
+        ]
+      );
+      // Not back to back, then disagreeing on whether a window is focused.
+      assert_eq!(events.len(), 9);
+      assert!(events[4..].iter().all(|event| !matches!(
+        event,
+        Coalesced::ActiveWindowChanged {
+          address: Some(_),
+          class: Some(_),
+          ..
+        }
+      )));
+    })
+  }
+}
@@ -0,0 +1,165 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+
+use super::Deadline;
+
+/// Stream returned by [`EventStreamExt::debounce_by`](super::EventStreamExt::debounce_by).
+pub struct Debounce<S: Stream, K, F> {
+  inner: S,
+  period: Duration,
+  key: F,
+  pending: HashMap<K, (Instant, S::Item)>,
+  deadline: Deadline,
+  ready: VecDeque<S::Item>,
+  done: bool,
+}
+
+impl<S, K, F> Debounce<S, K, F>
+where
+  S: Stream,
+  K: Eq + Hash + Clone,
+  F: FnMut(&S::Item) -> Option<K>,
+{
+  pub(crate) fn new(inner: S, period: Duration, key: F) -> Self {
+    Debounce {
+      inner,
+      period,
+      key,
+      pending: HashMap::new(),
+      deadline: Deadline::new(),
+      ready: VecDeque::new(),
+      done: false,
+    }
+  }
+
+  fn push(&mut self, item: S::Item) {
+    match (self.key)(&item) {
+      Some(key) => {
+        self
+          .pending
+          .insert(key, (Instant::now() + self.period, item));
+        self.arm();
+      }
+      None => self.ready.push_back(item),
+    }
+  }
+
+  /// Moves every item whose quiet period ended before `now` to the ready queue, oldest first.
+  fn release(&mut self, now: Instant) {
+    let mut expired: Vec<K> = self
+      .pending
+      .iter()
+      .filter(|(_, (at, _))| *at <= now)
+      .map(|(key, _)| key.clone())
+      .collect();
+    expired.sort_by_key(|key| self.pending[key].0);
+    for key in expired {
+      if let Some((_, item)) = self.pending.remove(&key) {
+        self.ready.push_back(item);
+      }
+    }
+    self.arm();
+  }
+
+  fn arm(&mut self) {
+    match self.pending.values().map(|(at, _)| *at).min() {
+      Some(at) => self.deadline.set(at),
+      None => self.deadline.clear(),
+    }
+  }
+}
+
+// Nothing but `inner` is ever polled through a pin, and `inner` is required to be `Unpin`.
+impl<S: Stream + Unpin, K, F> Unpin for Debounce<S, K, F> {}
+
+impl<S, K, F> Stream for Debounce<S, K, F>
+where
+  S: Stream + Unpin,
+  K: Eq + Hash + Clone,
+  F: FnMut(&S::Item) -> Option<K>,
+{
+  type Item = S::Item;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+    let this = &mut *self;
+    loop {
+      if let Some(item) = this.ready.pop_front() {
+        return Poll::Ready(Some(item));
+      }
+      if this.done {
+        if this.pending.is_empty() {
+          return Poll::Ready(None);
+        }
+        this.release(Instant::now() + this.period);
+        continue;
+      }
+
+      match Pin::new(&mut this.inner).poll_next(cx) {
+        Poll::Ready(Some(item)) => this.push(item),
+        Poll::Ready(None) => this.done = true,
+        Poll::Pending => {
+          if !this.deadline.poll_expired(cx) {
+            return Poll::Pending;
+          }
+          this.release(Instant::now());
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::runtime::block_on;
+  use crate::stream::{paced, EventStreamExt};
+  use futures_util::future::join;
+  use futures_util::{stream, StreamExt};
+  use std::sync::atomic::Ordering;
+  use std::time::Duration;
+
+  #[test]
+  fn keeps_last_item_per_key() {
+    block_on(async {
+      let items = stream::iter(vec![
+        ("a", 1),
+        ("b", 1),
+        ("a", 2),
+        ("x", 0),
+        ("a", 3),
+        ("b", 2),
+      ]);
+      let output: Vec<_> = items
+        .debounce_by(Duration::from_millis(10), |(key, _)| {
+          (*key != "x").then_some(*key)
+        })
+        .collect()
+        .await;
+
+      assert_eq!(output, vec![("x", 0), ("a", 3), ("b", 2)]);
+    })
+  }
+
+  #[test]
+  fn emits_once_the_key_went_quiet() {
+    block_on(async {
+      let short = Duration::from_millis(10);
+      let long = Duration::from_millis(200);
+      let (feed, receiver, closed) =
+        paced::feed(vec![(short, 1), (short, 2), (long, 3), (short, 4)]);
+      let debounced = receiver.debounce_by(Duration::from_millis(50), |_| Some(()));
+
+      let (_, output) = join(feed, async {
+        let output: Vec<u32> = debounced.take(2).collect().await;
+        assert!(!closed.load(Ordering::SeqCst));
+        output
+      })
+      .await;
+      assert_eq!(output, vec![2, 4]);
+    })
+  }
+}
@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+use tracing::warn;
+
+use crate::envelope::Envelope;
+use crate::events::{Event, WindowAddress};
+use crate::query::Client;
+use crate::{DispatchError, Dispatcher};
+
+/// An event produced by [`EventStreamExt::enrich`](super::EventStreamExt::enrich).
+///
+/// # Fields
+///
+/// * `event` - The event as it was received.
+/// * `client` - The window the event is about, as last known. `None` for events without a window
+///   address, and for windows the compositor no longer reports. For `closewindow` it is the window
+///   as it was before closing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enriched {
+  pub event: Event,
+  pub client: Option<Client>,
+}
+
+/// How long the cache is used as is after the `clients` query failed, rather than running it again
+/// for every event.
+const RETRY_AFTER: Duration = Duration::from_secs(5);
+
+type Refresh = Pin<Box<dyn Future<Output = Result<Vec<Client>, DispatchError>> + Send>>;
+
+/// Stream returned by [`EventStreamExt::enrich`](super::EventStreamExt::enrich).
+///
+/// The adapter keeps the reply of the `clients` query and patches it from the events passing
+/// through: `movewindowv2`, `windowtitlev2`, `changefloatingmode` and `pin` carry everything
+/// needed. The query only runs again for `openwindow`, for addresses not in the cache that the last
+/// query didn't miss either and, until
+/// the compositor is seen sending `windowtitlev2`, for `windowtitle`. An envelope waits for its
+/// query to finish, so the order of the stream is kept. After the query failed, it doesn't run
+/// again for a few seconds.
+pub struct Enrich<S> {
+  inner: S,
+  dispatcher: Dispatcher,
+  clients: HashMap<WindowAddress, Client>,
+  missing: HashSet<WindowAddress>,
+  pending: Option<Envelope>,
+  refresh: Option<Refresh>,
+  titles_in_events: bool,
+  retry_at: Option<Instant>,
+}
+
+impl<S> Enrich<S> {
+  pub(crate) fn new(inner: S, dispatcher: Dispatcher) -> Self {
+    Enrich {
+      inner,
+      dispatcher,
+      clients: HashMap::new(),
+      missing: HashSet::new(),
+      pending: None,
+      refresh: None,
+      titles_in_events: false,
+      retry_at: None,
+    }
+  }
+
+  fn needs_refresh(&self, event: &Event) -> bool {
+    if self.retry_at.is_some_and(|at| Instant::now() < at) {
+      return false;
+    }
+    match event {
+      Event::OpenWindow(_) => true,
+      Event::CloseWindow(_) => false,
+      Event::WindowTitle(_) if !self.titles_in_events => true,
+      _ => event.window_address().is_some_and(|address| {
+        !self.clients.contains_key(&address) && !self.missing.contains(&address)
+      }),
+    }
+  }
+
+  fn start_refresh(&mut self) {
+    let dispatcher = self.dispatcher.clone();
+    self.refresh = Some(Box::pin(async move { dispatcher.clients().await }));
+  }
+
+  fn finish_refresh(&mut self, result: Result<Vec<Client>, DispatchError>) {
+    match result {
+      Ok(clients) => {
+        self.retry_at = None;
+        self.clients = clients
+          .into_iter()
+          .map(|client| (client.address, client))
+          .collect();
+        // Until it opens or closes, a window the query doesn't list isn't queried for again.
+        let missed = self
+          .pending
+          .as_ref()
+          .and_then(|envelope| match &envelope.event {
+            Event::OpenWindow(_) => None,
+            event => event.window_address(),
+          });
+        if let Some(address) = missed.filter(|address| !self.clients.contains_key(address)) {
+          self.missing.insert(address);
+        }
+      }
+      Err(error) => {
+        warn!(error = %error, "Failed to refresh the client cache");
+        self.retry_at = Some(Instant::now() + RETRY_AFTER);
+      }
+    }
+  }
+
+  /// Patches the cache from `envelope` and attaches the window it is about.
+  fn enrich(&mut self, envelope: Envelope) -> Envelope<Enriched> {
+    let event = &envelope.event;
+    if let Event::OpenWindow(_) | Event::CloseWindow(_) = event {
+      if let Some(address) = event.window_address() {
+        self.missing.remove(&address);
+      }
+    }
+    let client = match event.window_address() {
+      Some(address) if matches!(event, Event::CloseWindow(_)) => self.clients.remove(&address),
+      Some(address) => self.clients.get_mut(&address).map(|client| {
+        match event {
+          // `movewindow` lacks the workspace ID, the `movewindowv2` following it has both.
+          Event::MoveWindowV2(e) => {
+            client.workspace.id = e.workspace_id;
+            client.workspace.name = e.workspace_name.clone();
+          }
+          Event::WindowTitleV2(e) => client.title = e.window_title.clone(),
+          Event::ChangeFloatingMode(e) => client.floating = e.floating,
+          Event::Pin(e) => client.pinned = e.pin_state,
+          _ => {}
+        }
+        client.clone()
+      }),
+      None => None,
+    };
+    if let Event::WindowTitleV2(_) = event {
+      self.titles_in_events = true;
+    }
+    envelope.map(|event| Enriched { event, client })
+  }
+}
+
+impl<S> Stream for Enrich<S>
+where
+  S: Stream<Item = Envelope> + Unpin,
+{
+  type Item = Envelope<Enriched>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = &mut *self;
+    loop {
+      if let Some(refresh) = this.refresh.as_mut() {
+        let Poll::Ready(result) = refresh.as_mut().poll(cx) else {
+          return Poll::Pending;
+        };
+        this.refresh = None;
+        this.finish_refresh(result);
+        if let Some(envelope) = this.pending.take() {
+          return Poll::Ready(Some(this.enrich(envelope)));
+        }
+      }
+
+      let Poll::Ready(next) = Pin::new(&mut this.inner).poll_next(cx) else {
+        return Poll::Pending;
+      };
+      let Some(envelope) = next else {
+        return Poll::Ready(None);
+      };
+      if !this.needs_refresh(&envelope.event) {
+        return Poll::Ready(Some(this.enrich(envelope)));
+      }
+      this.pending = Some(envelope);
+      this.start_refresh();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::envelope::ReceivedAt;
+  use crate::runtime::block_on;
+  use crate::stream::EventStreamExt;
+  use futures_util::{stream, StreamExt};
+  use hyprstream_proto::interpreter::Interpreter;
+
+  const CLIENTS: &str = r#"[
+    { "address": "0x1a", "workspace": { "id": 1, "name": "1" }, "class": "kitty", "title": "~" },
+    { "address": "0x2b", "workspace": { "id": 2, "name": "2" }, "class": "firefox", "title": "Docs" }
+  ]"#;
+
+  fn envelopes(lines: &[&str]) -> Vec<Envelope> {
+    lines
+      .iter()
+      .enumerate()
+      .map(|(i, line)| Envelope {
+        seq: i as u64 + 1,
+        received_at: ReceivedAt::now(),
+        raw: line.to_string(),
+        event: Interpreter::new(line).interpret().unwrap(),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn attaches_cached_clients_and_patches_them_from_events() {
+    block_on(async {
+      let (hyprland, requests) = crate::dispatch::fake::serve(|_| CLIENTS.to_string());
+      let input = envelopes(&[
+        "activewindowv2>>1a",
+        "windowtitlev2>>1a,vim",
+        "movewindowv2>>2b,3,3",
+        "workspace>>3",
+        "windowtitle>>2b",
+        "closewindow>>1a",
+        "activewindowv2>>1a",
+      ]);
+      let output: Vec<_> = stream::iter(input)
+        .enrich(Dispatcher::new(hyprland))
+        .collect()
+        .await;
+
+      let clients: Vec<_> = output
+        .iter()
+        .map(|envelope| {
+          let client = envelope.event.client.as_ref()?;
+          Some((client.title.as_str(), client.workspace.id))
+        })
+        .collect();
+      assert_eq!(
+        clients,
+        [
+          Some(("~", 1)),
+          Some(("vim", 1)),
+          Some(("Docs", 3)),
+          None,
+          Some(("Docs", 3)),
+          Some(("vim", 1)),
+          // Still in the fake's reply, but queried again since the cache lost it.
+          Some(("~", 1)),
+        ]
+      );
+      assert_eq!(output[3].seq, 4);
+      assert_eq!(*requests.lock().unwrap(), ["j/clients", "j/clients"]);
+    })
+  }
+
+  #[test]
+  fn queries_again_for_new_windows() {
+    block_on(async {
+      let (hyprland, requests) = crate::dispatch::fake::serve(|_| CLIENTS.to_string());
+      let input = envelopes(&["windowtitle>>2b", "openwindow>>1a,1,kitty,~", "urgent>>1a"]);
+      let output: Vec<_> = stream::iter(input)
+        .enrich(Dispatcher::new(hyprland))
+        .collect()
+        .await;
+
+      assert!(output
+        .iter()
+        .all(|envelope| envelope.event.client.is_some()));
+      assert_eq!(requests.lock().unwrap().len(), 2);
+    })
+  }
+
+  #[test]
+  fn queries_once_for_windows_it_misses() {
+    block_on(async {
+      let (hyprland, requests) = crate::dispatch::fake::serve(|_| CLIENTS.to_string());
+      let input = envelopes(&[
+        "urgent>>3c",
+        "windowtitlev2>>3c,vim",
+        "urgent>>3c",
+        "closewindow>>3c",
+        "urgent>>3c",
+      ]);
+      let output: Vec<_> = stream::iter(input)
+        .enrich(Dispatcher::new(hyprland))
+        .collect()
+        .await;
+
+      assert!(output
+        .iter()
+        .all(|envelope| envelope.event.client.is_none()));
+      // Once for the first `urgent`, and again after `closewindow` forgot the miss.
+      assert_eq!(requests.lock().unwrap().len(), 2);
+    })
+  }
+
+  #[test]
+  fn backs_off_after_a_failed_query() {
+    block_on(async {
+      let (hyprland, requests) = crate::dispatch::fake::serve(|_| "unknown request".to_string());
+      let input = envelopes(&["openwindow>>1a,1,kitty,~", "urgent>>1a", "windowtitle>>1a"]);
+      let output: Vec<_> = stream::iter(input)
+        .enrich(Dispatcher::new(hyprland))
+        .collect()
+        .await;
+
+      assert_eq!(output.len(), 3);
+      assert!(output
+        .iter()
+        .all(|envelope| envelope.event.client.is_none()));
+      assert_eq!(requests.lock().unwrap().len(), 1);
+    })
+  }
+
+  #[test]
+  fn movewindow_leaves_the_workspace_to_movewindowv2() {
+    block_on(async {
+      let (hyprland, _) = crate::dispatch::fake::serve(|_| CLIENTS.to_string());
+      let input = envelopes(&["movewindow>>1a,3", "movewindowv2>>1a,3,3"]);
+      let output: Vec<_> = stream::iter(input)
+        .enrich(Dispatcher::new(hyprland))
+        .collect()
+        .await;
+
+      let workspaces: Vec<_> = output
+        .iter()
+        .map(|envelope| {
+          let workspace = &envelope.event.client.as_ref().unwrap().workspace;
+          (workspace.id, workspace.name.as_str())
+        })
+        .collect();
+      assert_eq!(workspaces, [(1, "1"), (3, "3")]);
+    })
+  }
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+
+use super::Deadline;
+
+/// Stream returned by [`EventStreamExt::latest_by`](super::EventStreamExt::latest_by).
+pub struct LatestBy<S: Stream, K, F> {
+  inner: S,
+  window: Duration,
+  key: F,
+  order: Vec<K>,
+  latest: HashMap<K, S::Item>,
+  deadline: Deadline,
+  ready: VecDeque<S::Item>,
+  done: bool,
+}
+
+impl<S, K, F> LatestBy<S, K, F>
+where
+  S: Stream,
+  K: Eq + Hash + Clone,
+  F: FnMut(&S::Item) -> Option<K>,
+{
+  pub(crate) fn new(inner: S, window: Duration, key: F) -> Self {
+    LatestBy {
+      inner,
+      window,
+      key,
+      order: Vec::new(),
+      latest: HashMap::new(),
+      deadline: Deadline::new(),
+      ready: VecDeque::new(),
+      done: false,
+    }
+  }
+
+  fn push(&mut self, item: S::Item) {
+    let Some(key) = (self.key)(&item) else {
+      self.ready.push_back(item);
+      return;
+    };
+
+    if self.order.is_empty() {
+      self.deadline.set(Instant::now() + self.window);
+    }
+    if self.latest.insert(key.clone(), item).is_none() {
+      self.order.push(key);
+    }
+  }
+
+  fn release(&mut self) {
+    self.deadline.clear();
+    for key in self.order.drain(..) {
+      if let Some(item) = self.latest.remove(&key) {
+        self.ready.push_back(item);
+      }
+    }
+  }
+}
+
+// Nothing but `inner` is ever polled through a pin, and `inner` is required to be `Unpin`.
+impl<S: Stream + Unpin, K, F> Unpin for LatestBy<S, K, F> {}
+
+impl<S, K, F> Stream for LatestBy<S, K, F>
+where
+  S: Stream + Unpin,
+  K: Eq + Hash + Clone,
+  F: FnMut(&S::Item) -> Option<K>,
+{
+  type Item = S::Item;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+    let this = &mut *self;
+    loop {
+      if let Some(item) = this.ready.pop_front() {
+        return Poll::Ready(Some(item));
+      }
+      if this.done {
+        this.release();
+        return Poll::Ready(this.ready.pop_front());
+      }
+
+      match Pin::new(&mut this.inner).poll_next(cx) {
+        Poll::Ready(Some(item)) => this.push(item),
+        Poll::Ready(None) => this.done = true,
+        Poll::Pending => {
+          if !this.deadline.poll_expired(cx) {
+            return Poll::Pending;
+          }
+          this.release();
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::runtime::block_on;
+  use crate::stream::EventStreamExt;
+  use futures_util::StreamExt;
+  use std::time::Duration;
+
+  #[test]
+  fn emits_latest_per_key_in_first_seen_order() {
+    block_on(async {
+      let (sender, receiver) = futures_channel::mpsc::unbounded();
+      let mut latest = receiver.latest_by(Duration::from_millis(20), |(key, _): &(&str, u32)| {
+        Some(*key)
+      });
+
+      for item in [("b", 1), ("a", 1), ("b", 2)] {
+        sender.unbounded_send(item).unwrap();
+      }
+      assert_eq!(latest.next().await, Some(("b", 2)));
+      assert_eq!(latest.next().await, Some(("a", 1)));
+
+      sender.unbounded_send(("a", 2)).unwrap();
+      drop(sender);
+      assert_eq!(latest.next().await, Some(("a", 2)));
+      assert_eq!(latest.next().await, None);
+    })
+  }
+}
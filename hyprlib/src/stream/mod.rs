@@ -0,0 +1,173 @@
+mod coalesce;
+mod debounce;
+mod enrich;
+mod latest;
+mod throttle;
+
+pub use coalesce::{Coalesce, Coalesced};
+pub use debounce::Debounce;
+pub use enrich::{Enrich, Enriched};
+pub use latest::LatestBy;
+pub use throttle::{Edge, Throttle};
+
+use std::hash::Hash;
+use std::task::Context;
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+
+use crate::envelope::Envelope;
+use crate::runtime::Timer;
+use crate::screencast::ScreenSharing;
+use crate::Dispatcher;
+
+/// Adapters available on every event stream, such as a [`Subscription`](crate::Subscription) or
+/// the output of another adapter.
+///
+/// The rate limiting adapters take a `key` function. Items with the same key are limited
+/// together, items mapped to `None` pass through untouched. Use `|_| Some(())` to limit the whole
+/// stream as one.
+pub trait EventStreamExt: Stream + Sized {
+  /// Merges the v1/v2 event pairs Hyprland sends back to back into a single [`Coalesced`] event.
+  /// When the other half of a pair does not arrive within `timeout`, the half that did arrive is
+  /// emitted on its own with the missing fields left as `None`.
+  fn coalesce(self, timeout: Duration) -> Coalesce<Self>
+  where
+    Self: Stream<Item = Envelope>,
+  {
+    Coalesce::new(self, timeout)
+  }
+
+  /// Emits `true` when the first screencopy session starts and `false` when the last one stops,
+  /// see [`ScreencastTracker`](crate::screencast::ScreencastTracker).
+  fn screen_sharing(self) -> ScreenSharing<Self>
+  where
+    Self: Stream<Item = Envelope>,
+  {
+    ScreenSharing::new(self)
+  }
+
+  /// Holds each item back until no other item with the same key arrived for `period`, then emits
+  /// the last one.
+  ///
+  /// ```no_run
+  /// # use std::time::Duration;
+  /// # use hyprlib::{Hyprland, Listener};
+  /// use hyprlib::events::{Event, EventKind};
+  /// use hyprlib::stream::EventStreamExt;
+  ///
+  /// let listener = Listener::new(Hyprland::default());
+  /// let titles = listener
+  ///   .subscribe()
+  ///   .only(EventKind::WindowTitle)
+  ///   .debounce_by(Duration::from_millis(100), |envelope| match &envelope.event {
+  ///     Event::WindowTitle(title) => Some(title.window_address),
+  ///     _ => None,
+  ///   });
+  /// ```
+  fn debounce_by<K, F>(self, period: Duration, key: F) -> Debounce<Self, K, F>
+  where
+    K: Eq + Hash + Clone,
+    F: FnMut(&Self::Item) -> Option<K>,
+  {
+    Debounce::new(self, period, key)
+  }
+
+  /// Emits at most one item per key every `period`. `edge` chooses whether the first item of a
+  /// period, the last one, or both get through.
+  fn throttle_by<K, F>(self, period: Duration, edge: Edge, key: F) -> Throttle<Self, K, F>
+  where
+    K: Eq + Hash + Clone,
+    F: FnMut(&Self::Item) -> Option<K>,
+  {
+    Throttle::new(self, period, edge, key)
+  }
+
+  /// Collects items for `window` after the first one arrives, then emits the latest item of every
+  /// key seen in that window, in the order the keys first appeared.
+  fn latest_by<K, F>(self, window: Duration, key: F) -> LatestBy<Self, K, F>
+  where
+    K: Eq + Hash + Clone,
+    F: FnMut(&Self::Item) -> Option<K>,
+  {
+    LatestBy::new(self, window, key)
+  }
+
+  /// Attaches the [`Client`](crate::query::Client) each event is about, from a cache of the
+  /// `clients` query kept current by the events themselves, see [`Enrich`].
+  fn enrich(self, dispatcher: Dispatcher) -> Enrich<Self> {
+    Enrich::new(self, dispatcher)
+  }
+}
+
+impl<S> EventStreamExt for S where S: Stream + Sized {}
+
+/// A resettable timer shared by the adapters in this module.
+struct Deadline {
+  timer: Option<Timer>,
+}
+
+impl Deadline {
+  fn new() -> Self {
+    Deadline { timer: None }
+  }
+
+  fn set(&mut self, at: Instant) {
+    match self.timer.as_mut() {
+      Some(timer) => timer.reset(at),
+      None => self.timer = Some(Timer::at(at)),
+    }
+  }
+
+  fn clear(&mut self) {
+    self.timer = None;
+  }
+
+  /// Returns `true` once the deadline has passed, clearing it. Registers the waker otherwise.
+  fn poll_expired(&mut self, cx: &mut Context<'_>) -> bool {
+    let expired = match self.timer.as_mut() {
+      Some(timer) => timer.poll_expired(cx).is_ready(),
+      None => false,
+    };
+    if expired {
+      self.timer = None;
+    }
+    expired
+  }
+}
+
+/// Drives the timers of the adapters in tests, as ending the input stream flushes everything.
+#[cfg(test)]
+pub(crate) mod paced {
+  use std::future::Future;
+  use std::sync::atomic::{AtomicBool, Ordering};
+  use std::sync::Arc;
+  use std::time::Duration;
+
+  use futures_channel::mpsc;
+
+  use crate::runtime::sleep;
+
+  /// Sends every item after its pause, then keeps the channel open a while longer. The flag is set
+  /// right before it closes, so an item seen while it is unset was released by a timer.
+  pub(crate) fn feed<T>(
+    items: Vec<(Duration, T)>,
+  ) -> (
+    impl Future<Output = ()>,
+    mpsc::UnboundedReceiver<T>,
+    Arc<AtomicBool>,
+  ) {
+    let (sender, receiver) = mpsc::unbounded();
+    let closed = Arc::new(AtomicBool::new(false));
+    let closing = closed.clone();
+    let feed = async move {
+      for (pause, item) in items {
+        sleep(pause).await;
+        sender.unbounded_send(item).unwrap();
+      }
+      sleep(Duration::from_millis(300)).await;
+      closing.store(true, Ordering::SeqCst);
+    };
+    (feed, receiver, closed)
+  }
+}
@@ -0,0 +1,259 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+
+use super::Deadline;
+
+/// Which items [`EventStreamExt::throttle_by`](super::EventStreamExt::throttle_by) lets through
+/// in each period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+  /// The first item is emitted immediately, the rest of the period is dropped.
+  Leading,
+  /// The last item of the period is emitted when the period ends.
+  Trailing,
+  /// The first item is emitted immediately, and the last one when the period ends if more items
+  /// arrived in between.
+  Both,
+}
+
+impl Edge {
+  fn leading(self) -> bool {
+    matches!(self, Edge::Leading | Edge::Both)
+  }
+
+  fn trailing(self) -> bool {
+    matches!(self, Edge::Trailing | Edge::Both)
+  }
+}
+
+/// A throttling period in progress for one key.
+struct Window<T> {
+  ends_at: Instant,
+  trailing: Option<T>,
+}
+
+/// Stream returned by [`EventStreamExt::throttle_by`](super::EventStreamExt::throttle_by).
+pub struct Throttle<S: Stream, K, F> {
+  inner: S,
+  period: Duration,
+  edge: Edge,
+  key: F,
+  windows: HashMap<K, Window<S::Item>>,
+  deadline: Deadline,
+  ready: VecDeque<S::Item>,
+  done: bool,
+}
+
+impl<S, K, F> Throttle<S, K, F>
+where
+  S: Stream,
+  K: Eq + Hash + Clone,
+  F: FnMut(&S::Item) -> Option<K>,
+{
+  pub(crate) fn new(inner: S, period: Duration, edge: Edge, key: F) -> Self {
+    Throttle {
+      inner,
+      period,
+      edge,
+      key,
+      windows: HashMap::new(),
+      deadline: Deadline::new(),
+      ready: VecDeque::new(),
+      done: false,
+    }
+  }
+
+  fn push(&mut self, item: S::Item) {
+    let Some(key) = (self.key)(&item) else {
+      self.ready.push_back(item);
+      return;
+    };
+
+    match self.windows.get_mut(&key) {
+      Some(window) => {
+        if self.edge.trailing() {
+          window.trailing = Some(item);
+        }
+      }
+      None => {
+        let mut window = Window {
+          ends_at: Instant::now() + self.period,
+          trailing: None,
+        };
+        if self.edge.leading() {
+          self.ready.push_back(item);
+        } else {
+          window.trailing = Some(item);
+        }
+        self.windows.insert(key, window);
+        self.arm();
+      }
+    }
+  }
+
+  /// Closes every window that ended before `now`. A window that emits a trailing item starts a
+  /// new one, so the next item for that key is still limited.
+  fn release(&mut self, now: Instant) {
+    let mut ended: Vec<K> = self
+      .windows
+      .iter()
+      .filter(|(_, window)| window.ends_at <= now)
+      .map(|(key, _)| key.clone())
+      .collect();
+    ended.sort_by_key(|key| self.windows[key].ends_at);
+    for key in ended {
+      let Some(window) = self.windows.get_mut(&key) else {
+        continue;
+      };
+      match window.trailing.take() {
+        Some(item) if !self.done => {
+          window.ends_at = now + self.period;
+          self.ready.push_back(item);
+        }
+        Some(item) => {
+          self.windows.remove(&key);
+          self.ready.push_back(item);
+        }
+        None => {
+          self.windows.remove(&key);
+        }
+      }
+    }
+    self.arm();
+  }
+
+  fn arm(&mut self) {
+    match self.windows.values().map(|window| window.ends_at).min() {
+      Some(at) => self.deadline.set(at),
+      None => self.deadline.clear(),
+    }
+  }
+}
+
+// Nothing but `inner` is ever polled through a pin, and `inner` is required to be `Unpin`.
+impl<S: Stream + Unpin, K, F> Unpin for Throttle<S, K, F> {}
+
+impl<S, K, F> Stream for Throttle<S, K, F>
+where
+  S: Stream + Unpin,
+  K: Eq + Hash + Clone,
+  F: FnMut(&S::Item) -> Option<K>,
+{
+  type Item = S::Item;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+    let this = &mut *self;
+    loop {
+      if let Some(item) = this.ready.pop_front() {
+        return Poll::Ready(Some(item));
+      }
+      if this.done {
+        if this.windows.is_empty() {
+          return Poll::Ready(None);
+        }
+        this.release(Instant::now() + this.period);
+        continue;
+      }
+
+      match Pin::new(&mut this.inner).poll_next(cx) {
+        Poll::Ready(Some(item)) => this.push(item),
+        Poll::Ready(None) => this.done = true,
+        Poll::Pending => {
+          if !this.deadline.poll_expired(cx) {
+            return Poll::Pending;
+          }
+          this.release(Instant::now());
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Edge;
+  use crate::runtime::block_on;
+  use crate::stream::{paced, EventStreamExt};
+  use futures_util::future::join;
+  use futures_util::{stream, StreamExt};
+  use std::sync::atomic::Ordering;
+  use std::time::Duration;
+
+  fn burst() -> impl futures_core::Stream<Item = u32> + Unpin {
+    stream::iter(vec![1, 2, 3, 4])
+  }
+
+  #[test]
+  fn leading_edge_keeps_first_item() {
+    block_on(async {
+      let period = Duration::from_secs(60);
+      let output: Vec<_> = burst()
+        .throttle_by(period, Edge::Leading, |_| Some(()))
+        .collect()
+        .await;
+      assert_eq!(output, vec![1]);
+    })
+  }
+
+  #[test]
+  fn trailing_edge_keeps_last_item() {
+    block_on(async {
+      let period = Duration::from_secs(60);
+      let output: Vec<_> = burst()
+        .throttle_by(period, Edge::Trailing, |_| Some(()))
+        .collect()
+        .await;
+      assert_eq!(output, vec![4]);
+    })
+  }
+
+  #[test]
+  fn both_edges_per_key() {
+    block_on(async {
+      let period = Duration::from_secs(60);
+      let output: Vec<_> = burst()
+        .throttle_by(period, Edge::Both, |n| Some(n % 2))
+        .collect()
+        .await;
+      assert_eq!(output, vec![1, 2, 3, 4]);
+    })
+  }
+
+  /// Two items 10ms apart, then one 200ms later, throttled with a 50ms period.
+  fn throttled_while_open(edge: Edge, count: usize) -> Vec<u32> {
+    block_on(async {
+      let short = Duration::from_millis(10);
+      let long = Duration::from_millis(200);
+      let (feed, receiver, closed) = paced::feed(vec![(short, 1), (short, 2), (long, 3)]);
+      let throttled = receiver.throttle_by(Duration::from_millis(50), edge, |_| Some(()));
+
+      let (_, output) = join(feed, async {
+        let output: Vec<u32> = throttled.take(count).collect().await;
+        assert!(!closed.load(Ordering::SeqCst));
+        output
+      })
+      .await;
+      output
+    })
+  }
+
+  #[test]
+  fn leading_edge_opens_a_new_period_after_a_quiet_one() {
+    assert_eq!(throttled_while_open(Edge::Leading, 2), vec![1, 3]);
+  }
+
+  #[test]
+  fn trailing_edge_emits_when_the_period_ends() {
+    assert_eq!(throttled_while_open(Edge::Trailing, 2), vec![2, 3]);
+  }
+
+  #[test]
+  fn both_edges_emit_the_trailing_item_on_time() {
+    assert_eq!(throttled_while_open(Edge::Both, 3), vec![1, 2, 3]);
+  }
+}
@@ -0,0 +1,114 @@
+//! Typed payloads for `custom` events.
+//!
+//! `hyprctl dispatch event DATA` makes Hyprland emit `custom>>DATA`, which turns the event socket
+//! into a small message bus. This module encodes a serde type into DATA as `TAG:JSON`, so several
+//! payload types can share the bus and each consumer only decodes its own.
+//!
+//! ```
+//! use hyprstream_proto::custom::CustomPayload;
+//! use hyprstream_proto::events::Event;
+//! use hyprstream_proto::Dispatch;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct ToggleBar {
+//!   visible: bool,
+//! }
+//!
+//! impl CustomPayload for ToggleBar {
+//!   const TAG: &'static str = "toggle-bar";
+//! }
+//!
+//! // Sent with `hyprlib::Dispatcher::dispatch`, or written to the request socket as
+//! // `dispatch event toggle-bar:{"visible":false}`.
+//! let dispatch = Dispatch::custom(&ToggleBar { visible: false }).unwrap();
+//! assert_eq!(dispatch.command(), r#"event toggle-bar:{"visible":false}"#);
+//!
+//! let Dispatch::Event(data) = dispatch else { unreachable!() };
+//! let event = Event::from("custom", &data);
+//! assert!(!event.custom::<ToggleBar>().unwrap().unwrap().visible);
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::events::Event;
+use crate::Dispatch;
+
+/// A payload type carried by `custom` events.
+///
+/// `TAG` tells payload types apart on the bus. It must not contain `:`.
+pub trait CustomPayload: Serialize + DeserializeOwned {
+  const TAG: &'static str;
+}
+
+/// Encodes `payload` into the data of a `custom` event.
+pub fn encode<P: CustomPayload>(payload: &P) -> serde_json::Result<String> {
+  Ok(format!("{}:{}", P::TAG, serde_json::to_string(payload)?))
+}
+
+/// Decodes the data of a `custom` event. Returns `None` when the data was not tagged with
+/// `P::TAG`, and an error when it was but the JSON doesn't match `P`.
+pub fn decode<P: CustomPayload>(data: &str) -> Option<serde_json::Result<P>> {
+  let (tag, json) = data.split_once(':')?;
+  (tag == P::TAG).then(|| serde_json::from_str(json))
+}
+
+impl Dispatch {
+  /// A [`Dispatch::Event`] carrying a typed payload.
+  pub fn custom<P: CustomPayload>(payload: &P) -> serde_json::Result<Self> {
+    encode(payload).map(Dispatch::Event)
+  }
+}
+
+impl Event {
+  /// Decodes this event as a typed payload. Returns `None` for anything but a `custom` event
+  /// tagged with `P::TAG`.
+  pub fn custom<P: CustomPayload>(&self) -> Option<serde_json::Result<P>> {
+    match self {
+      Event::Custom(data) => decode(data),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde::Deserialize;
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  struct Notify {
+    summary: String,
+    urgent: bool,
+  }
+
+  impl CustomPayload for Notify {
+    const TAG: &'static str = "notify";
+  }
+
+  #[test]
+  fn round_trip() {
+    let payload = Notify {
+      summary: "build done:\nall green".to_string(),
+      urgent: true,
+    };
+    let Dispatch::Event(data) = Dispatch::custom(&payload).unwrap() else {
+      unreachable!()
+    };
+    assert!(!data.contains('\n'));
+
+    let event = Event::from("custom", &data);
+    assert_eq!(event.custom::<Notify>().unwrap().unwrap(), payload);
+    assert!(Event::Custom("other:{}".to_string())
+      .custom::<Notify>()
+      .is_none());
+    assert!(Event::Custom("notify:{}".to_string())
+      .custom::<Notify>()
+      .unwrap()
+      .is_err());
+  }
+}
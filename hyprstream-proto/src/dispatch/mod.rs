@@ -0,0 +1,54 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::events::WindowAddress;
+
+/// A dispatcher to run through `Dispatcher::dispatch`, equivalent to `hyprctl dispatch`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dispatch {
+  /// Emits `custom>>DATA` on the event socket. See [`custom`](crate::custom) for typed payloads.
+  Event(String),
+  /// Focuses the window with the given address.
+  FocusWindow(WindowAddress),
+  /// Runs a shell command, such as `firefox`.
+  Exec(String),
+  /// Switches to a workspace, given as an ID, a name or a relative target such as `+1`.
+  Workspace(String),
+}
+
+impl Dispatch {
+  /// The command as written to the request socket, without the `dispatch ` prefix.
+  pub fn command(&self) -> String {
+    match self {
+      Dispatch::Event(data) => format!("event {}", data),
+      Dispatch::FocusWindow(address) => format!("focuswindow address:{:#x}", address),
+      Dispatch::Exec(command) => format!("exec {}", command),
+      Dispatch::Workspace(workspace) => format!("workspace {}", workspace),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn focus_window_uses_the_prefixed_address() {
+    assert_eq!(
+      Dispatch::FocusWindow(WindowAddress(0x55d5a8b2c9a0)).command(),
+      "focuswindow address:0x55d5a8b2c9a0"
+    );
+  }
+
+  #[test]
+  fn exec_and_workspace_pass_their_argument_through() {
+    assert_eq!(
+      Dispatch::Exec("firefox --new-window".to_string()).command(),
+      "exec firefox --new-window"
+    );
+    assert_eq!(
+      Dispatch::Workspace("+1".to_string()).command(),
+      "workspace +1"
+    );
+  }
+}
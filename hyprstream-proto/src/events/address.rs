@@ -0,0 +1,82 @@
+use alloc::string::String;
+use core::fmt;
+use core::num::ParseIntError;
+use core::str::FromStr;
+
+use serde::Deserialize;
+
+/// `WindowAddress` identifies a window for as long as it is mapped.
+///
+/// Events carry it as bare hex (`64cea2525760`), while queries and dispatchers use a `0x`
+/// prefix. Both forms parse; `Display` writes the event form and `{:#x}` the prefixed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WindowAddress(pub u64);
+
+impl FromStr for WindowAddress {
+  type Err = ParseIntError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let hex = s
+      .strip_prefix("0x")
+      .or_else(|| s.strip_prefix("0X"))
+      .unwrap_or(s);
+    u64::from_str_radix(hex, 16).map(WindowAddress)
+  }
+}
+
+impl fmt::Display for WindowAddress {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:x}", self.0)
+  }
+}
+
+impl fmt::LowerHex for WindowAddress {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::LowerHex::fmt(&self.0, f)
+  }
+}
+
+impl serde::Serialize for WindowAddress {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{:#x}", self))
+  }
+}
+
+impl<'de> Deserialize<'de> for WindowAddress {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let address = String::deserialize(deserializer)?;
+    address.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+/// A comma separated list of window addresses, borrowed from an event's data. Every address has
+/// been checked when the event was parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressList<'a>(&'a str);
+
+impl<'a> AddressList<'a> {
+  /// `None` when one of the addresses isn't valid hex.
+  pub(crate) fn new(list: &'a str) -> Option<Self> {
+    let list = AddressList(list);
+    list
+      .parts()
+      .all(|address| address.parse::<WindowAddress>().is_ok())
+      .then_some(list)
+  }
+
+  fn parts(&self) -> impl Iterator<Item = &'a str> {
+    let list = self.0;
+    (!list.is_empty())
+      .then(|| list.split(','))
+      .into_iter()
+      .flatten()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = WindowAddress> + 'a {
+    self.parts().map(|address| address.parse().unwrap())
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+}
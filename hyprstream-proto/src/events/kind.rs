@@ -0,0 +1,168 @@
+use core::fmt;
+use core::ops::{BitAnd, BitOr, BitOrAssign, Not};
+
+use super::EventKind;
+
+impl EventKind {
+  const fn bit(self) -> u64 {
+    1 << self as u64
+  }
+}
+
+/// `EventKinds` is a set of [`EventKind`]s, used to select which events a subscription receives.
+///
+/// Single kinds combine with `|` into a set, and the associated constants group related kinds:
+///
+/// ```
+/// use hyprstream_proto::events::{EventKind, EventKinds};
+///
+/// let kinds = EventKinds::WINDOW | EventKinds::WORKSPACE | EventKind::Submap;
+/// assert!(kinds.contains(EventKind::OpenWindow));
+/// assert!(!kinds.contains(EventKind::OpenLayer));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EventKinds(u64);
+
+impl EventKinds {
+  pub const NONE: EventKinds = EventKinds(0);
+  pub const ALL: EventKinds = EventKinds(u64::MAX);
+
+  /// Workspace lifecycle, focus and special workspace events.
+  pub const WORKSPACE: EventKinds = EventKinds::of(&[
+    EventKind::Workspace,
+    EventKind::WorkspaceV2,
+    EventKind::CreateWorkspace,
+    EventKind::CreateWorkspaceV2,
+    EventKind::DestroyWorkspace,
+    EventKind::DestroyWorkspaceV2,
+    EventKind::MoveWorkspace,
+    EventKind::MoveWorkspaceV2,
+    EventKind::RenameWorkspace,
+    EventKind::ActiveSpecial,
+    EventKind::ActiveSpecialV2,
+  ]);
+
+  /// Window lifecycle, focus and state events.
+  pub const WINDOW: EventKinds = EventKinds::of(&[
+    EventKind::ActiveWindow,
+    EventKind::ActiveWindowV2,
+    EventKind::OpenWindow,
+    EventKind::CloseWindow,
+    EventKind::MoveWindow,
+    EventKind::MoveWindowV2,
+    EventKind::WindowTitle,
+    EventKind::WindowTitleV2,
+    EventKind::Fullscreen,
+    EventKind::ChangeFloatingMode,
+    EventKind::Urgent,
+    EventKind::Minimize,
+    EventKind::Pin,
+    EventKind::Bell,
+  ]);
+
+  /// Monitor hotplug and focus events.
+  pub const MONITOR: EventKinds = EventKinds::of(&[
+    EventKind::FocusedMon,
+    EventKind::MonitorAdded,
+    EventKind::MonitorAddedV2,
+    EventKind::MonitorRemoved,
+    EventKind::MonitorRemovedV2,
+  ]);
+
+  /// Layer surface events.
+  pub const LAYER: EventKinds = EventKinds::of(&[EventKind::OpenLayer, EventKind::CloseLayer]);
+
+  /// Window group events.
+  pub const GROUP: EventKinds = EventKinds::of(&[
+    EventKind::IgnoreGroupLock,
+    EventKind::LockGroups,
+    EventKind::ToggleGroup,
+    EventKind::MoveIntoGroup,
+    EventKind::MoveOutOfGroup,
+  ]);
+
+  /// Keyboard layout and keybind submap events.
+  pub const INPUT: EventKinds = EventKinds::of(&[EventKind::ActiveLayout, EventKind::Submap]);
+
+  /// Builds a set from a list of kinds.
+  pub const fn of(kinds: &[EventKind]) -> EventKinds {
+    let mut bits = 0;
+    let mut i = 0;
+    while i < kinds.len() {
+      bits |= kinds[i].bit();
+      i += 1;
+    }
+    EventKinds(bits)
+  }
+
+  /// The kinds in either set, like `|` but usable in constants.
+  pub const fn union(self, other: EventKinds) -> EventKinds {
+    EventKinds(self.0 | other.0)
+  }
+
+  pub const fn contains(self, kind: EventKind) -> bool {
+    self.0 & kind.bit() != 0
+  }
+
+  pub const fn is_empty(self) -> bool {
+    self.0 == 0
+  }
+
+  /// Iterates over the kinds in the set, in declaration order.
+  pub fn iter(self) -> impl Iterator<Item = EventKind> {
+    EventKind::ALL
+      .iter()
+      .copied()
+      .filter(move |kind| self.contains(*kind))
+  }
+}
+
+impl From<EventKind> for EventKinds {
+  fn from(kind: EventKind) -> Self {
+    EventKinds(kind.bit())
+  }
+}
+
+impl<T: Into<EventKinds>> BitOr<T> for EventKinds {
+  type Output = EventKinds;
+
+  fn bitor(self, rhs: T) -> EventKinds {
+    EventKinds(self.0 | rhs.into().0)
+  }
+}
+
+impl<T: Into<EventKinds>> BitOr<T> for EventKind {
+  type Output = EventKinds;
+
+  fn bitor(self, rhs: T) -> EventKinds {
+    EventKinds(self.bit() | rhs.into().0)
+  }
+}
+
+impl<T: Into<EventKinds>> BitOrAssign<T> for EventKinds {
+  fn bitor_assign(&mut self, rhs: T) {
+    self.0 |= rhs.into().0;
+  }
+}
+
+impl<T: Into<EventKinds>> BitAnd<T> for EventKinds {
+  type Output = EventKinds;
+
+  fn bitand(self, rhs: T) -> EventKinds {
+    EventKinds(self.0 & rhs.into().0)
+  }
+}
+
+impl Not for EventKinds {
+  type Output = EventKinds;
+
+  fn not(self) -> EventKinds {
+    EventKinds(!self.0)
+  }
+}
+
+impl fmt::Debug for EventKinds {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.iter()).finish()
+  }
+}
@@ -0,0 +1,245 @@
+/// Defines every event Hyprland sends from a single table.
+///
+/// Each entry gives the payload struct's docs, its name, the name of the event on the wire and
+/// its fields in wire order:
+///
+/// ```text
+/// /// Emitted when ...
+/// MoveWorkspace = "moveworkspace" [commas in workspace_name] {
+///   workspace_name: String,
+///   monitor_name: String,
+/// }
+/// ```
+///
+/// Fields are separated by commas, and Hyprland doesn't escape them. At most one field can
+/// contain commas; it is the last one unless named with `[commas in FIELD]`. Field types implement
+/// [`WireField`](super::wire::WireField).
+///
+/// An entry can end with `+ resolved { FIELD: TYPE, .. }` for fields the payload doesn't carry but
+/// a lookup can fill in, such as the mode of a `fullscreen` event. They are left at their
+/// `Default` when parsing, aren't written back to the wire, and their types have to be `Copy` to
+/// appear in the borrowed form.
+///
+/// From the table this generates the owned payload structs with their [`Wire`](super::wire::Wire)
+/// impls, their [`borrowed`](super::borrowed) counterparts with the parsers, the [`Event`],
+/// [`EventRef`] and [`EventKind`] enums, the mapping between kinds and wire names and the
+/// [`Display`](core::fmt::Display) impl of [`Event`]. `configreloaded`, `custom` and unknown events
+/// carry no struct and are added here.
+macro_rules! events {
+  ($(
+    $(#[$attr:meta])*
+    $name:ident = $wire:literal $([commas in $commas:ident])? {
+      $($field:ident: $ty:ty),* $(,)?
+    } $(+ resolved {
+      $($resolved:ident: $resolved_ty:ty),* $(,)?
+    })?
+  )*) => {
+    $(
+      $(#[$attr])*
+      #[derive(Debug, Clone, PartialEq)]
+      pub struct $name {
+        $(pub $field: $ty,)*
+        $($(pub $resolved: $resolved_ty,)*)?
+      }
+
+      impl Wire for $name {
+        fn write_wire(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+          let mut separator = "";
+          $(
+            f.write_str(separator)?;
+            self.$field.write_field(f)?;
+            separator = ",";
+          )*
+          let _ = separator;
+          Ok(())
+        }
+      }
+    )*
+
+    /// Borrowed forms of the event payloads, which [`EventRef`](crate::events::EventRef) parses without
+    /// allocating. Strings are slices of the line and window address lists are
+    /// [`AddressList`](crate::events::AddressList)s; all other fields have the same type as in the owned
+    /// payload.
+    pub mod borrowed {
+      use super::*;
+
+      $(
+        #[doc = concat!("Borrowed form of [`", stringify!($name), "`](super::", stringify!($name), ").")]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub struct $name<'a> {
+          $(pub $field: <$ty as WireField>::Ref<'a>,)*
+          $($(pub $resolved: $resolved_ty,)*)?
+        }
+
+        impl<'a> $name<'a> {
+          pub(super) fn parse(data: &'a str) -> Option<Self> {
+            const FIELDS: &[&str] = &[$(stringify!($field)),*];
+            let commas = events!(@commas $name FIELDS $($commas)?);
+            let [$($field),*] = split_fields::<{ FIELDS.len() }>(data, commas)?;
+            Some($name {
+              $($field: <$ty as WireField>::parse_field($field)?,)*
+              $($($resolved: Default::default(),)*)?
+            })
+          }
+
+          pub fn to_owned(self) -> super::$name {
+            super::$name {
+              $($field: <$ty as WireField>::to_owned_field(self.$field),)*
+              $($($resolved: self.$resolved,)*)?
+            }
+          }
+        }
+      )*
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Event {
+      $($name($name),)*
+      /// Emitted when the config is done reloading. Carries no data.
+      ConfigReloaded,
+      /// Data sent with `hyprctl dispatch event DATA`, see [`custom`](crate::custom).
+      Custom(String),
+      /// An event this crate doesn't know yet, kept as it arrived.
+      Unknown { name: String, data: String },
+    }
+
+    /// An [`Event`] whose strings borrow from the line it was parsed from.
+    ///
+    /// Parsing one doesn't allocate, which suits code that looks at an event and drops it, such as
+    /// filters and metrics. [`EventRef::to_owned`] gives the [`Event`] when it has to be kept.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum EventRef<'a> {
+      $($name(borrowed::$name<'a>),)*
+      ConfigReloaded,
+      Custom(&'a str),
+      Unknown { name: &'a str, data: &'a str },
+    }
+
+    /// `EventKind` names the variant of an [`Event`] without carrying its payload. It can be
+    /// recovered from the wire name alone, which lets filters run before the data is parsed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum EventKind {
+      $($name,)*
+      ConfigReloaded,
+      Custom,
+      /// Any event name this crate doesn't know.
+      Unknown,
+    }
+
+    // `EventKinds` is a `u64` with one bit per kind, so a 65th kind must not compile.
+    const _: () = assert!(EventKind::ALL.len() <= 64);
+
+    impl EventKind {
+      /// Every kind, in declaration order.
+      pub const ALL: &'static [EventKind] = &[
+        $(EventKind::$name,)*
+        EventKind::ConfigReloaded,
+        EventKind::Custom,
+        EventKind::Unknown,
+      ];
+
+      /// Looks up the kind for an event name as it appears before `>>` on the wire.
+      pub fn from_name(event_type: &str) -> Option<Self> {
+        let kind = match event_type {
+          $($wire => EventKind::$name,)*
+          "configreloaded" => EventKind::ConfigReloaded,
+          "custom" => EventKind::Custom,
+          _ => return None,
+        };
+        Some(kind)
+      }
+
+      /// The event name as it appears before `>>` on the wire. `None` for
+      /// [`EventKind::Unknown`], whose name is only known per event, see [`Event::name`].
+      pub fn name(self) -> Option<&'static str> {
+        let name = match self {
+          $(EventKind::$name => $wire,)*
+          EventKind::ConfigReloaded => "configreloaded",
+          EventKind::Custom => "custom",
+          EventKind::Unknown => return None,
+        };
+        Some(name)
+      }
+    }
+
+    impl Event {
+      pub fn kind(&self) -> EventKind {
+        match self {
+          $(Event::$name(_) => EventKind::$name,)*
+          Event::ConfigReloaded => EventKind::ConfigReloaded,
+          Event::Custom(_) => EventKind::Custom,
+          Event::Unknown { .. } => EventKind::Unknown,
+        }
+      }
+    }
+
+    impl<'a> EventRef<'a> {
+      /// Parses an event from its name and data. `None` when the data doesn't match the format of
+      /// a known event; unknown names give [`EventRef::Unknown`].
+      pub fn parse(event_type: &'a str, data: &'a str) -> Option<Self> {
+        let event = match EventKind::from_name(event_type) {
+          $(Some(EventKind::$name) => EventRef::$name(borrowed::$name::parse(data)?),)*
+          Some(EventKind::ConfigReloaded) => EventRef::ConfigReloaded,
+          Some(EventKind::Custom) => EventRef::Custom(data),
+          Some(EventKind::Unknown) | None => EventRef::Unknown {
+            name: event_type,
+            data,
+          },
+        };
+        Some(event)
+      }
+
+      pub fn kind(&self) -> EventKind {
+        match self {
+          $(EventRef::$name(_) => EventKind::$name,)*
+          EventRef::ConfigReloaded => EventKind::ConfigReloaded,
+          EventRef::Custom(_) => EventKind::Custom,
+          EventRef::Unknown { .. } => EventKind::Unknown,
+        }
+      }
+
+      pub fn to_owned(self) -> Event {
+        match self {
+          $(EventRef::$name(event) => Event::$name(event.to_owned()),)*
+          EventRef::ConfigReloaded => Event::ConfigReloaded,
+          EventRef::Custom(data) => Event::Custom(data.to_string()),
+          EventRef::Unknown { name, data } => Event::Unknown {
+            name: name.to_string(),
+            data: data.to_string(),
+          },
+        }
+      }
+    }
+
+    /// Writes the event in its wire format, see [`Event::to_wire`].
+    ///
+    /// Hyprland doesn't escape anything, so the format can't represent a newline in any field, nor
+    /// a comma in a field other than the one allowed to contain them. Such events are written
+    /// as-is and will not parse back to the same event.
+    impl fmt::Display for Event {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}>>", self.name())?;
+        match self {
+          $(Event::$name(event) => event.write_wire(f),)*
+          Event::ConfigReloaded => Ok(()),
+          Event::Custom(data) => f.write_str(data),
+          Event::Unknown { data, .. } => f.write_str(data),
+        }
+      }
+    }
+  };
+
+  (@commas $name:ident $fields:ident) => {
+    $fields.len() - 1
+  };
+  (@commas $name:ident $fields:ident $commas:ident) => {{
+    // Fails to compile when the field doesn't exist.
+    let _ = |event: $name| {
+      let _ = event.$commas;
+    };
+    $fields
+      .iter()
+      .position(|field| *field == stringify!($commas))
+      .unwrap()
+  }};
+}
@@ -0,0 +1,942 @@
+mod address;
+#[macro_use]
+mod macros;
+mod kind;
+#[cfg(test)]
+mod roundtrip;
+mod wire;
+
+pub use address::{AddressList, WindowAddress};
+pub use kind::EventKinds;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::monitor::MonitorDescription;
+use wire::{split_fields, Wire, WireField};
+
+events! {
+  /// `Workspace` is emitted on workspace change. Is emitted ONLY when a
+  ///  user requests a workspace change, and is not emitted on mouse
+  ///  movements (see activemon)
+  ///
+  /// # Fields
+  /// * `workspace_name` - Represents the WORKSPACENAME of the workspace.
+  ///
+  /// # Format
+  /// workspace>>WORKSPACENAME
+  Workspace = "workspace" {
+    workspace_name: String,
+  }
+
+  /// `WorkspaceV2` is emitted on workspace change. Is emitted ONLY when a
+  ///  user requests a workspace change, and is not emitted on mouse
+  ///  movements (see activemon)
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_id` - Represents the WORKSPACEID of the workspace.
+  /// * `workspace_name` - Represents the WORKSPACENAME of the workspace.
+  ///
+  /// # Format
+  /// workspacev2>>WORKSPACEID,WORKSPACENAME
+  WorkspaceV2 = "workspacev2" {
+    workspace_id: i32,
+    workspace_name: String,
+  }
+
+  /// `FocusedMon` is emitted when the active monitor is changed.
+  ///
+  /// # Fields
+  ///
+  /// * `monitor_name` - Represents the MONNAME / monitor name of the active monitor.
+  /// * `workspace_name` - Represents the WORKSPACENAME of the active monitor.
+  ///
+  /// # Format
+  /// focusedmon>>MONNAME,WORKSPACENAME
+  FocusedMon = "focusedmon" {
+    monitor_name: String,
+    workspace_name: String,
+  }
+
+  /// `ActiveWindow` is emitted when the active window is changed.
+  ///
+  /// # Fields
+  ///
+  /// * `window_class` - Represents the WINDOWCLASS of the active window.
+  /// * `window_title` - Represents the WINDOWTITLE of the active window.
+  ///
+  /// # Format
+  /// activewindow>>WINDOWCLASS,WINDOWTITLE
+  ActiveWindow = "activewindow" {
+    window_class: String,
+    window_title: String,
+  }
+
+  /// `ActiveWindowV2` is emitted when the active window is changed.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - Represents the WINDOWADDRESS of the active window, `None` when no
+  ///   window is focused.
+  ///
+  /// # Format
+  /// activewindowv2>>WINDOWADDRESS
+  ActiveWindowV2 = "activewindowv2" {
+    window_address: Option<WindowAddress>,
+  }
+
+  /// Emitted when a fullscreen status of a window changes.
+  ///
+  /// Hyprland reports maximizing as entering fullscreen too, and its payload is only `0` or `1`:
+  /// the line cannot tell maximized from fullscreen. `mode` is therefore `None` when parsed, and
+  /// hyprlib's `Dispatcher::resolve_fullscreen_mode` fills it in from the focused window.
+  ///
+  /// # Fields
+  ///
+  /// * `enter_fullscreen` - 0/1 (exit fullscreen / enter fullscreen)
+  /// * `mode` - The mode entered, [`FullscreenMode::None`] when leaving fullscreen. Not on the
+  ///   wire; `None` until looked up.
+  ///
+  /// # Format
+  /// fullscreen>>0/1 (exit fullscreen / enter fullscreen)
+  Fullscreen = "fullscreen" {
+    enter_fullscreen: bool,
+  } + resolved {
+    mode: Option<FullscreenMode>,
+  }
+
+  /// Emitted when a monitor is removed (disconnected).
+  ///
+  /// # Fields
+  ///
+  /// * `monitor_name` - The name of the monitor that was removed.
+  ///
+  /// # Format
+  /// monitorremoved>>MONITORNAME
+  MonitorRemoved = "monitorremoved" {
+    monitor_name: String,
+  }
+
+  /// Emitted when a monitor is removed (disconnected).
+  ///
+  /// Sent since Hyprland v0.47.0.
+  ///
+  /// # Fields
+  ///
+  /// * `monitor_id` - The ID of the monitor that was removed.
+  /// * `monitor_name` - The name of the monitor that was removed.
+  /// * `monitor_description` - The description of the monitor that was removed.
+  ///
+  /// # Format
+  /// monitorremovedv2>>MONITORID,MONITORNAME,MONITORDESCRIPTION
+  MonitorRemovedV2 = "monitorremovedv2" {
+    monitor_id: u32,
+    monitor_name: String,
+    monitor_description: String,
+  }
+
+  /// Emitted when a monitor is added (connected).
+  ///
+  /// # Fields
+  ///
+  /// * `monitor_name` - The name of the monitor that was added.
+  ///
+  /// # Format
+  /// monitoradded>>MONITORNAME
+  MonitorAdded = "monitoradded" {
+    monitor_name: String,
+  }
+
+  /// Emitted when a monitor is added (connected).
+  ///
+  /// # Fields
+  ///
+  /// * `monitor_id` - The ID of the monitor that was added.
+  /// * `monitor_name` - The name of the monitor that was added.
+  /// * `monitor_description` - The description of the monitor that was added.
+  ///
+  /// # Format
+  /// monitoraddedv2>>MONITORID,MONITORNAME,MONITORDESCRIPTION
+  MonitorAddedV2 = "monitoraddedv2" {
+    monitor_id: u32,
+    monitor_name: String,
+    monitor_description: String,
+  }
+
+  /// `CreateWorkspace` is emitted when a workspace is created.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_name` - Represents the WORKSPACENAME of the created workspace.
+  ///
+  /// # Format
+  /// createworkspace>>WORKSPACENAME
+  CreateWorkspace = "createworkspace" {
+    workspace_name: String,
+  }
+
+  /// `CreateWorkspaceV2` is emitted when a workspace is created.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_id` - Represents the WORKSPACEID of the created workspace.
+  /// * `workspace_name` - Represents the WORKSPACENAME of the created workspace.
+  ///
+  /// # Format
+  /// createworkspacev2>>WORKSPACEID,WORKSPACENAME
+  CreateWorkspaceV2 = "createworkspacev2" {
+    workspace_id: i32,
+    workspace_name: String,
+  }
+
+  /// `DestroyWorkspace` is emitted when a workspace is destroyed.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_name` - Represents the WORKSPACENAME of the destroyed workspace.
+  ///
+  /// # Format
+  /// destroyworkspace>>WORKSPACENAME
+  DestroyWorkspace = "destroyworkspace" {
+    workspace_name: String,
+  }
+
+  /// `DestroyWorkspaceV2` is emitted when a workspace is destroyed.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_id` - Represents the WORKSPACEID of the destroyed workspace.
+  /// * `workspace_name` - Represents the WORKSPACENAME of the destroyed workspace.
+  ///
+  /// # Format
+  /// destroyworkspacev2>>WORKSPACEID,WORKSPACENAME
+  DestroyWorkspaceV2 = "destroyworkspacev2" {
+    workspace_id: i32,
+    workspace_name: String,
+  }
+
+  /// `MoveWorkspace` is emitted when a workspace is moved to a different monitor.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_name` - Represents the WORKSPACENAME of the moved workspace.
+  /// * `monitor_name` - Represents the MONNAME of the monitor the workspace is moved to.
+  ///
+  /// # Format
+  /// moveworkspace>>WORKSPACENAME,MONNAME
+  MoveWorkspace = "moveworkspace" [commas in workspace_name] {
+    workspace_name: String,
+    monitor_name: String,
+  }
+
+  /// `MoveWorkspaceV2` is emitted when a workspace is moved to a different monitor.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_id` - Represents the WORKSPACEID of the moved workspace.
+  /// * `workspace_name` - Represents the WORKSPACENAME of the moved workspace.
+  /// * `monitor_name` - Represents the MONNAME of the monitor the workspace is moved to.
+  ///
+  /// # Format
+  /// moveworkspacev2>>WORKSPACEID,WORKSPACENAME,MONNAME
+  MoveWorkspaceV2 = "moveworkspacev2" [commas in workspace_name] {
+    workspace_id: i32,
+    workspace_name: String,
+    monitor_name: String,
+  }
+
+  /// Emitted when a workspace is renamed.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_id` - The ID of the workspace that was renamed.
+  /// * `new_name` - The new name of the workspace.
+  ///
+  /// # Format
+  /// renameworkspace>>WORKSPACEID,NEWNAME
+  RenameWorkspace = "renameworkspace" {
+    workspace_id: i32,
+    new_name: String,
+  }
+
+  /// Emitted when the special workspace opened in a monitor changes (closing results in an empty WORKSPACENAME).
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_name` - The name of the workspace.
+  /// * `monitor_name` - The name of the monitor.
+  ///
+  /// # Format
+  /// activespecial>>WORKSPACENAME,MONNAME
+  ActiveSpecial = "activespecial" [commas in workspace_name] {
+    workspace_name: String,
+    monitor_name: String,
+  }
+
+  /// Emitted when the special workspace opened in a monitor changes (closing results in an empty
+  /// WORKSPACEID and WORKSPACENAME).
+  ///
+  /// Sent since Hyprland v0.47.0.
+  ///
+  /// # Fields
+  ///
+  /// * `workspace_id` - The ID of the workspace, `None` when the special workspace was closed.
+  ///   Special workspaces have negative IDs.
+  /// * `workspace_name` - The name of the workspace.
+  /// * `monitor_name` - The name of the monitor.
+  ///
+  /// # Format
+  /// activespecialv2>>WORKSPACEID,WORKSPACENAME,MONNAME
+  ActiveSpecialV2 = "activespecialv2" [commas in workspace_name] {
+    workspace_id: Option<i32>,
+    workspace_name: String,
+    monitor_name: String,
+  }
+
+  /// Emitted on a layout change of the active keyboard.
+  ///
+  /// # Fields
+  ///
+  /// * `keyboard_name` - The name of the keyboard.
+  /// * `layout_name` - The name of the layout.
+  ///
+  /// # Format
+  /// activelayout>>KEYBOARDNAME,LAYOUTNAME
+  ActiveLayout = "activelayout" {
+    keyboard_name: String,
+    layout_name: String,
+  }
+
+  /// Emitted when a window is opened.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  /// * `workspace_name` - The name of the workspace.
+  /// * `window_class` - The class of the window.
+  /// * `window_title` - The title of the window.
+  ///
+  /// # Format
+  /// openwindow>>WINDOWADDRESS,WORKSPACENAME,WINDOWCLASS,WINDOWTITLE
+  OpenWindow = "openwindow" {
+    window_address: WindowAddress,
+    workspace_name: String,
+    window_class: String,
+    window_title: String,
+  }
+
+  /// Emitted when a window is closed.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  ///
+  /// # Format
+  /// closewindow>>WINDOWADDRESS
+  CloseWindow = "closewindow" {
+    window_address: WindowAddress,
+  }
+
+  /// Emitted when a window is moved to a workspace.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  /// * `workspace_name` - The name of the workspace.
+  ///
+  /// # Format
+  /// movewindow>>WINDOWADDRESS,WORKSPACENAME
+  MoveWindow = "movewindow" {
+    window_address: WindowAddress,
+    workspace_name: String,
+  }
+
+  /// Emitted when a window is moved to a workspace.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  /// * `workspace_id` - The ID of the workspace.
+  /// * `workspace_name` - The name of the workspace.
+  ///
+  /// # Format
+  /// movewindowv2>>WINDOWADDRESS,WORKSPACEID,WORKSPACENAME
+  MoveWindowV2 = "movewindowv2" {
+    window_address: WindowAddress,
+    workspace_id: i32,
+    workspace_name: String,
+  }
+
+  /// Emitted when a window title changes.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window whose title has changed.
+  ///
+  /// # Format
+  /// windowtitle>>WINDOWADDRESS
+  WindowTitle = "windowtitle" {
+    window_address: WindowAddress,
+  }
+
+  /// Emitted when a window title changes. Unlike [`WindowTitle`] it carries the new title.
+  ///
+  /// Sent since Hyprland v0.42.0.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window whose title has changed.
+  /// * `window_title` - The new title of the window.
+  ///
+  /// # Format
+  /// windowtitlev2>>WINDOWADDRESS,WINDOWTITLE
+  WindowTitleV2 = "windowtitlev2" {
+    window_address: WindowAddress,
+    window_title: String,
+  }
+
+  /// Emitted when a layerSurface is mapped.
+  ///
+  /// # Fields
+  ///
+  /// * `namespace` - The namespace of the layerSurface.
+  ///
+  /// # Format
+  /// openlayer>>NAMESPACE
+  OpenLayer = "openlayer" {
+    namespace: String,
+  }
+
+  /// Emitted when a layerSurface is unmapped.
+  ///
+  /// # Fields
+  ///
+  /// * `namespace` - The namespace of the layerSurface.
+  ///
+  /// # Format
+  /// closelayer>>NAMESPACE
+  CloseLayer = "closelayer" {
+    namespace: String,
+  }
+
+  /// Emitted when a keybind submap changes. Empty means default.
+  ///
+  /// # Fields
+  ///
+  /// * `submap_name` - The name of the submap.
+  ///
+  /// # Format
+  /// submap>>SUBMAPNAME
+  Submap = "submap" {
+    submap_name: String,
+  }
+
+  /// Emitted when a window changes its floating mode.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  /// * `floating` - Whether the window is in floating mode (`true`) or not (`false`).
+  ///
+  /// # Format
+  /// changefloatingmode>>WINDOWADDRESS,FLOATING
+  ChangeFloatingMode = "changefloatingmode" {
+    window_address: WindowAddress,
+    floating: bool,
+  }
+
+  /// Emitted when a window requests an urgent state.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  ///
+  /// # Format
+  /// urgent>>WINDOWADDRESS
+  Urgent = "urgent" {
+    window_address: WindowAddress,
+  }
+
+  /// Emitted when a window requests a change to its minimized state.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  /// * `minimized` - Whether the window is minimized (`true`) or not (`false`).
+  ///
+  /// # Format
+  /// minimize>>WINDOWADDRESS,MINIMIZED
+  Minimize = "minimize" {
+    window_address: WindowAddress,
+    minimized: bool,
+  }
+
+  /// Emitted when a screencopy state of a client changes. Note there might be multiple separate clients.
+  /// `hyprlib::screencast::ScreencastTracker` counts them.
+  ///
+  /// # Fields
+  ///
+  /// * `state` - The screencopy state (`true` for active, `false` for inactive).
+  /// * `owner` - What the screencopy session captures.
+  ///
+  /// # Format
+  /// screencast>>STATE,OWNER
+  Screencast = "screencast" {
+    state: bool,
+    owner: ScreencastOwner,
+  }
+
+  /// Emitted when ignoregrouplock is toggled.
+  ///
+  /// # Fields
+  ///
+  /// * `state` - The new state of ignoregrouplock (`true` for enabled, `false` for disabled).
+  ///
+  /// # Format
+  /// ignoregrouplock>>STATE
+  IgnoreGroupLock = "ignoregrouplock" {
+    state: bool,
+  }
+
+  /// Emitted when lockgroups is toggled.
+  ///
+  /// # Fields
+  ///
+  /// * `state` - The new state of lockgroups (`true` for enabled, `false` for disabled).
+  ///
+  /// # Format
+  /// lockgroups>>STATE
+  LockGroups = "lockgroups" {
+    state: bool,
+  }
+
+  /// Emitted when the togglegroup command is used.
+  ///
+  /// # Fields
+  ///
+  /// * `state` - Whether the group was created (`true`) or destroyed (`false`).
+  /// * `addresses` - The addresses of the windows in the group.
+  ///
+  /// # Format
+  /// togglegroup>>STATE,WINDOWADDRESS,WINDOWADDRESS,...
+  ToggleGroup = "togglegroup" {
+    state: bool,
+    addresses: Vec<WindowAddress>,
+  }
+
+  /// Emitted when a window is merged into a group.
+  ///
+  /// # Fields
+  ///
+  /// * `address` - The address of the window that was merged.
+  ///
+  /// # Format
+  /// moveintogroup>>WINDOWADDRESS
+  MoveIntoGroup = "moveintogroup" {
+    address: WindowAddress,
+  }
+
+  /// Emitted when a window is removed from a group.
+  ///
+  /// # Fields
+  ///
+  /// * `address` - The address of the window that was removed.
+  ///
+  /// # Format
+  /// moveoutofgroup>>WINDOWADDRESS
+  MoveOutOfGroup = "moveoutofgroup" {
+    address: WindowAddress,
+  }
+
+  /// Emitted when a window is pinned or unpinned.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window.
+  /// * `pin_state` - Whether the window is pinned (`true`) or unpinned (`false`).
+  ///
+  /// # Format
+  /// pin>>WINDOWADDRESS,PINSTATE
+  Pin = "pin" {
+    window_address: WindowAddress,
+    pin_state: bool,
+  }
+
+  /// Emitted when a window rings the system bell through `xdg-system-bell-v1`.
+  ///
+  /// Sent since Hyprland v0.48.0.
+  ///
+  /// # Fields
+  ///
+  /// * `window_address` - The address of the window, `None` when the bell isn't tied to a window.
+  ///
+  /// # Format
+  /// bell>>WINDOWADDRESS
+  Bell = "bell" {
+    window_address: Option<WindowAddress>,
+  }
+}
+
+impl Event {
+  /// Parses an event from its name and data. `None` when the data doesn't match the format of a
+  /// known event; unknown names give [`Event::Unknown`].
+  pub fn parse(event_type: &str, data: &str) -> Option<Self> {
+    EventRef::parse(event_type, data).map(EventRef::to_owned)
+  }
+
+  /// Parses an event from its name and data.
+  ///
+  /// # Panics
+  ///
+  /// When the data doesn't match the format of the event, see [`Event::parse`] for a fallible
+  /// version.
+  pub fn from(event_type: &str, data: &str) -> Self {
+    Event::parse(event_type, data)
+      .unwrap_or_else(|| panic!("[Event::from()]: Invalid {} data: {}", event_type, data))
+  }
+
+  /// The name of the event as it appears before `>>` on the wire.
+  pub fn name(&self) -> &str {
+    match self {
+      Event::Unknown { name, .. } => name,
+      _ => self.kind().name().unwrap_or_default(),
+    }
+  }
+
+  /// The window the event is about, for events that carry a single window address. `None` for
+  /// other events, and for `activewindowv2` and `bell` without a window.
+  pub fn window_address(&self) -> Option<WindowAddress> {
+    match self {
+      Event::ActiveWindowV2(event) => event.window_address,
+      Event::Bell(event) => event.window_address,
+      Event::OpenWindow(OpenWindow { window_address, .. })
+      | Event::CloseWindow(CloseWindow { window_address })
+      | Event::MoveWindow(MoveWindow { window_address, .. })
+      | Event::MoveWindowV2(MoveWindowV2 { window_address, .. })
+      | Event::WindowTitle(WindowTitle { window_address })
+      | Event::WindowTitleV2(WindowTitleV2 { window_address, .. })
+      | Event::ChangeFloatingMode(ChangeFloatingMode { window_address, .. })
+      | Event::Urgent(Urgent { window_address })
+      | Event::Minimize(Minimize { window_address, .. })
+      | Event::Pin(Pin { window_address, .. })
+      | Event::MoveIntoGroup(MoveIntoGroup {
+        address: window_address,
+      })
+      | Event::MoveOutOfGroup(MoveOutOfGroup {
+        address: window_address,
+      }) => Some(*window_address),
+      _ => None,
+    }
+  }
+
+  /// The `name>>data` line Hyprland would send for this event, without the trailing newline.
+  /// Parsing it again with [`Event::from`] gives back an equal event.
+  pub fn to_wire(&self) -> String {
+    self.to_string()
+  }
+}
+
+impl<'a> EventRef<'a> {
+  /// Parses a whole `name>>data` line, without its trailing newline. `None` when the line is
+  /// malformed or its data doesn't match the format of the event.
+  pub fn from_line(line: &'a str) -> Option<Self> {
+    let (event_type, data) = split_line(line)?;
+    EventRef::parse(event_type, data)
+  }
+}
+
+impl EventKind {
+  /// The kind of a whole `name>>data` line, without looking at its data. `None` when the line is
+  /// malformed or the name unknown.
+  pub fn from_line(line: &str) -> Option<Self> {
+    EventKind::from_name(split_line(line)?.0)
+  }
+}
+
+/// Splits a line at the first `>>` into the event name and its data. Same as
+/// `line.split_once(">>")`, but `memchr` finds the separator much faster in lines this short.
+pub(crate) fn split_line(line: &str) -> Option<(&str, &str)> {
+  let bytes = line.as_bytes();
+  memchr::memchr_iter(b'>', bytes)
+    .find(|&i| bytes.get(i + 1) == Some(&b'>'))
+    .map(|i| (&line[..i], &line[i + 2..]))
+}
+
+/// The fullscreen mode of a window, as reported by the `fullscreen` and `fullscreenClient` fields
+/// of the `clients` and `activewindow` queries.
+///
+/// Hyprland tracks two modes per window: the internal one it lays the window out with, and the
+/// one the client was told about. They differ for fake fullscreen, where an application believes
+/// it is fullscreen while staying tiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FullscreenMode {
+  #[default]
+  None,
+  /// Fills the monitor's work area, bars and gaps around it stay visible.
+  Maximized,
+  /// Covers the whole monitor.
+  Fullscreen,
+}
+
+impl FullscreenMode {
+  /// Maps Hyprland's mode bitmask (1 maximized, 2 fullscreen). With both bits set fullscreen wins,
+  /// as it does in Hyprland.
+  pub fn from_bits(bits: u8) -> Self {
+    if bits & 2 != 0 {
+      FullscreenMode::Fullscreen
+    } else if bits & 1 != 0 {
+      FullscreenMode::Maximized
+    } else {
+      FullscreenMode::None
+    }
+  }
+
+  pub fn bits(self) -> u8 {
+    match self {
+      FullscreenMode::None => 0,
+      FullscreenMode::Maximized => 1,
+      FullscreenMode::Fullscreen => 2,
+    }
+  }
+}
+
+/// Accepts the integer bitmask of Hyprland v0.42.0 and later, and the boolean older releases sent.
+/// Those releases reported maximized windows as fullscreen too, so `true` maps to `Fullscreen`;
+/// [`Client`](crate::query::Client) tells them apart with the `fullscreenMode` sent next to it.
+impl<'de> serde::Deserialize<'de> for FullscreenMode {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct Visitor;
+
+    impl serde::de::Visitor<'_> for Visitor {
+      type Value = FullscreenMode;
+
+      fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a fullscreen mode bitmask or boolean")
+      }
+
+      fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<FullscreenMode, E> {
+        Ok(match v {
+          true => FullscreenMode::Fullscreen,
+          false => FullscreenMode::None,
+        })
+      }
+
+      fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<FullscreenMode, E> {
+        Ok(FullscreenMode::from_bits(v as u8))
+      }
+
+      fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<FullscreenMode, E> {
+        Ok(FullscreenMode::from_bits(v as u8))
+      }
+    }
+
+    deserializer.deserialize_any(Visitor)
+  }
+}
+
+impl serde::Serialize for FullscreenMode {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u8(self.bits())
+  }
+}
+
+impl MonitorAddedV2 {
+  /// The description split into make, model and serial, with `monitor_name` as the connector.
+  pub fn description(&self) -> MonitorDescription {
+    MonitorDescription {
+      connector: Some(self.monitor_name.clone()),
+      ..MonitorDescription::parse(&self.monitor_description)
+    }
+  }
+}
+
+impl MonitorRemovedV2 {
+  /// The description split into make, model and serial, with `monitor_name` as the connector.
+  pub fn description(&self) -> MonitorDescription {
+    MonitorDescription {
+      connector: Some(self.monitor_name.clone()),
+      ..MonitorDescription::parse(&self.monitor_description)
+    }
+  }
+}
+
+/// What a screencopy session captures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScreencastOwner {
+  /// A whole monitor is shared (0 on the wire).
+  Monitor,
+  /// A single window is shared (1 on the wire).
+  Window,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn group_events() {
+    assert_eq!(
+      Event::from("togglegroup", "1,64cea2525760,64cea2522380"),
+      Event::ToggleGroup(ToggleGroup {
+        state: true,
+        addresses: vec![WindowAddress(0x64cea2525760), WindowAddress(0x64cea2522380)],
+      })
+    );
+    assert_eq!(
+      Event::from("moveoutofgroup", "64cea2522380"),
+      Event::MoveOutOfGroup(MoveOutOfGroup {
+        address: WindowAddress(0x64cea2522380),
+      })
+    );
+    assert_eq!("0x64cea2522380".parse(), Ok(WindowAddress(0x64cea2522380)));
+    assert_eq!(
+      format!("{:#x}", WindowAddress(0x64cea2522380)),
+      "0x64cea2522380"
+    );
+    assert_eq!(
+      Event::from("moveoutofgroup", "64cea2522380").window_address(),
+      Some(WindowAddress(0x64cea2522380))
+    );
+    assert_eq!(Event::from("activewindowv2", "").window_address(), None);
+    assert_eq!(
+      Event::from("togglegroup", "0,64cea2525760").window_address(),
+      None
+    );
+  }
+
+  #[test]
+  fn v2_events() {
+    assert_eq!(
+      Event::from("windowtitlev2", "64cea2525760,~/src, hyprstream"),
+      Event::WindowTitleV2(WindowTitleV2 {
+        window_address: WindowAddress(0x64cea2525760),
+        window_title: "~/src, hyprstream".to_string(),
+      })
+    );
+    assert_eq!(
+      Event::from("activespecialv2", "-98,special:scratch,DP-2"),
+      Event::ActiveSpecialV2(ActiveSpecialV2 {
+        workspace_id: Some(-98),
+        workspace_name: "special:scratch".to_string(),
+        monitor_name: "DP-2".to_string(),
+      })
+    );
+    assert_eq!(
+      Event::from("activespecialv2", ",,DP-2"),
+      Event::ActiveSpecialV2(ActiveSpecialV2 {
+        workspace_id: None,
+        workspace_name: String::new(),
+        monitor_name: "DP-2".to_string(),
+      })
+    );
+    assert_eq!(
+      Event::from("monitorremovedv2", "1,DP-2,Dell Inc. DELL U2720Q 8XYZ123"),
+      Event::MonitorRemovedV2(MonitorRemovedV2 {
+        monitor_id: 1,
+        monitor_name: "DP-2".to_string(),
+        monitor_description: "Dell Inc. DELL U2720Q 8XYZ123".to_string(),
+      })
+    );
+    assert_eq!(
+      Event::from("bell", ""),
+      Event::Bell(Bell {
+        window_address: None
+      })
+    );
+  }
+
+  #[test]
+  fn monitor_added_description() {
+    let Event::MonitorAddedV2(added) = Event::from(
+      "monitoraddedv2",
+      "2,HDMI-A-1,Gigabyte Technology Co., Ltd. M27Q 22110B012345",
+    ) else {
+      panic!("expected monitoraddedv2");
+    };
+    let description = added.description();
+    assert_eq!(description.make, "Gigabyte Technology Co., Ltd.");
+    assert_eq!(description.model, "M27Q");
+    assert_eq!(description.serial.as_deref(), Some("22110B012345"));
+    assert_eq!(description.connector.as_deref(), Some("HDMI-A-1"));
+  }
+
+  #[test]
+  fn special_workspaces_and_invalid_data() {
+    assert_eq!(
+      Event::from("workspacev2", "-98,special:scratch"),
+      Event::WorkspaceV2(WorkspaceV2 {
+        workspace_id: -98,
+        workspace_name: "special:scratch".to_string(),
+      })
+    );
+    assert_eq!(
+      Event::from("activewindowv2", ""),
+      Event::ActiveWindowV2(ActiveWindowV2 {
+        window_address: None
+      })
+    );
+    assert_eq!(Event::parse("movewindowv2", "garbage"), None);
+    assert_eq!(Event::parse("screencast", "1,2"), None);
+  }
+
+  #[test]
+  fn lines_split_at_the_first_separator() {
+    for line in [
+      "workspace>>2",
+      "activewindow>>kitty,a >> b",
+      "a>b>>c",
+      ">>>",
+      "submap>>",
+      "x>",
+      "",
+    ] {
+      assert_eq!(split_line(line), line.split_once(">>"), "{:?}", line);
+    }
+    assert_eq!(
+      EventKind::from_line("workspace>>2"),
+      Some(EventKind::Workspace)
+    );
+    assert_eq!(EventKind::from_line("workspace"), None);
+  }
+
+  #[test]
+  fn every_name_is_found() {
+    for &kind in EventKind::ALL {
+      if let Some(name) = kind.name() {
+        assert_eq!(EventKind::from_name(name), Some(kind));
+      }
+    }
+    for name in ["", "w", "workspac", "workspacee", "customm", "WORKSPACE"] {
+      assert_eq!(EventKind::from_name(name), None, "{:?}", name);
+    }
+  }
+
+  #[test]
+  fn borrowed_events() {
+    let line = "togglegroup>>1,64cea2525760,64cea2522380";
+    let Some(EventRef::ToggleGroup(group)) = EventRef::from_line(line) else {
+      panic!("expected togglegroup");
+    };
+    assert!(group.state);
+    assert_eq!(
+      group.addresses.iter().collect::<Vec<_>>(),
+      vec![WindowAddress(0x64cea2525760), WindowAddress(0x64cea2522380)]
+    );
+    assert_eq!(
+      EventRef::from_line(line).unwrap().to_owned(),
+      Event::from("togglegroup", "1,64cea2525760,64cea2522380")
+    );
+
+    let line = "openwindow>>55d5a8b2c9a0,2,kitty,~/src, hyprstream";
+    let Some(EventRef::OpenWindow(open)) = EventRef::from_line(line) else {
+      panic!("expected openwindow");
+    };
+    assert_eq!(open.window_title, "~/src, hyprstream");
+    assert!(std::ptr::eq(open.window_title, &line[line.len() - 17..]));
+
+    assert_eq!(EventRef::from_line("togglegroup>>1,nothex"), None);
+    assert_eq!(EventRef::from_line("no separator"), None);
+  }
+}
@@ -0,0 +1,265 @@
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+
+use super::*;
+use crate::interpreter::Interpreter;
+
+/// A field followed by another one: anything but a comma or a line break.
+fn field() -> impl Strategy<Value = String> {
+  "[^,\r\n]{0,24}"
+}
+
+/// The last field of an event, which may contain commas and `>>`.
+fn last_field() -> impl Strategy<Value = String> {
+  "[^\r\n]{0,48}"
+}
+
+fn address() -> impl Strategy<Value = WindowAddress> {
+  any::<u64>().prop_map(WindowAddress)
+}
+
+fn event() -> impl Strategy<Value = Event> {
+  prop_oneof![
+    last_field().prop_map(|workspace_name| Event::Workspace(Workspace { workspace_name })),
+    (any::<i32>(), last_field()).prop_map(|(workspace_id, workspace_name)| {
+      Event::WorkspaceV2(WorkspaceV2 {
+        workspace_id,
+        workspace_name,
+      })
+    }),
+    (field(), last_field()).prop_map(|(monitor_name, workspace_name)| {
+      Event::FocusedMon(FocusedMon {
+        monitor_name,
+        workspace_name,
+      })
+    }),
+    (field(), last_field()).prop_map(|(window_class, window_title)| {
+      Event::ActiveWindow(ActiveWindow {
+        window_class,
+        window_title,
+      })
+    }),
+    prop::option::of(address())
+      .prop_map(|window_address| Event::ActiveWindowV2(ActiveWindowV2 { window_address })),
+    any::<bool>().prop_map(|enter_fullscreen| {
+      Event::Fullscreen(Fullscreen {
+        enter_fullscreen,
+        mode: None,
+      })
+    }),
+    last_field().prop_map(|monitor_name| Event::MonitorRemoved(MonitorRemoved { monitor_name })),
+    (any::<u32>(), field(), last_field()).prop_map(
+      |(monitor_id, monitor_name, monitor_description)| {
+        Event::MonitorRemovedV2(MonitorRemovedV2 {
+          monitor_id,
+          monitor_name,
+          monitor_description,
+        })
+      }
+    ),
+    last_field().prop_map(|monitor_name| Event::MonitorAdded(MonitorAdded { monitor_name })),
+    (any::<u32>(), field(), last_field()).prop_map(
+      |(monitor_id, monitor_name, monitor_description)| {
+        Event::MonitorAddedV2(MonitorAddedV2 {
+          monitor_id,
+          monitor_name,
+          monitor_description,
+        })
+      }
+    ),
+    last_field()
+      .prop_map(|workspace_name| Event::CreateWorkspace(CreateWorkspace { workspace_name })),
+    (any::<i32>(), last_field()).prop_map(|(workspace_id, workspace_name)| {
+      Event::CreateWorkspaceV2(CreateWorkspaceV2 {
+        workspace_id,
+        workspace_name,
+      })
+    }),
+    last_field()
+      .prop_map(|workspace_name| Event::DestroyWorkspace(DestroyWorkspace { workspace_name })),
+    (any::<i32>(), last_field()).prop_map(|(workspace_id, workspace_name)| {
+      Event::DestroyWorkspaceV2(DestroyWorkspaceV2 {
+        workspace_id,
+        workspace_name,
+      })
+    }),
+    (last_field(), field()).prop_map(|(workspace_name, monitor_name)| {
+      Event::MoveWorkspace(MoveWorkspace {
+        workspace_name,
+        monitor_name,
+      })
+    }),
+    (any::<i32>(), last_field(), field()).prop_map(
+      |(workspace_id, workspace_name, monitor_name)| {
+        Event::MoveWorkspaceV2(MoveWorkspaceV2 {
+          workspace_id,
+          workspace_name,
+          monitor_name,
+        })
+      }
+    ),
+    (any::<i32>(), last_field()).prop_map(|(workspace_id, new_name)| {
+      Event::RenameWorkspace(RenameWorkspace {
+        workspace_id,
+        new_name,
+      })
+    }),
+    (last_field(), field()).prop_map(|(workspace_name, monitor_name)| {
+      Event::ActiveSpecial(ActiveSpecial {
+        workspace_name,
+        monitor_name,
+      })
+    }),
+    (any::<Option<i32>>(), last_field(), field()).prop_map(
+      |(workspace_id, workspace_name, monitor_name)| {
+        Event::ActiveSpecialV2(ActiveSpecialV2 {
+          workspace_id,
+          workspace_name,
+          monitor_name,
+        })
+      }
+    ),
+    (field(), last_field()).prop_map(|(keyboard_name, layout_name)| {
+      Event::ActiveLayout(ActiveLayout {
+        keyboard_name,
+        layout_name,
+      })
+    }),
+    (address(), field(), field(), last_field()).prop_map(
+      |(window_address, workspace_name, window_class, window_title)| {
+        Event::OpenWindow(OpenWindow {
+          window_address,
+          workspace_name,
+          window_class,
+          window_title,
+        })
+      }
+    ),
+    address().prop_map(|window_address| Event::CloseWindow(CloseWindow { window_address })),
+    (address(), last_field()).prop_map(|(window_address, workspace_name)| {
+      Event::MoveWindow(MoveWindow {
+        window_address,
+        workspace_name,
+      })
+    }),
+    (address(), any::<i32>(), last_field()).prop_map(
+      |(window_address, workspace_id, workspace_name)| {
+        Event::MoveWindowV2(MoveWindowV2 {
+          window_address,
+          workspace_id,
+          workspace_name,
+        })
+      }
+    ),
+    last_field().prop_map(|namespace| Event::OpenLayer(OpenLayer { namespace })),
+    last_field().prop_map(|namespace| Event::CloseLayer(CloseLayer { namespace })),
+    last_field().prop_map(|submap_name| Event::Submap(Submap { submap_name })),
+    (address(), any::<bool>()).prop_map(|(window_address, floating)| {
+      Event::ChangeFloatingMode(ChangeFloatingMode {
+        window_address,
+        floating,
+      })
+    }),
+    address().prop_map(|window_address| Event::Urgent(Urgent { window_address })),
+    (address(), any::<bool>()).prop_map(|(window_address, minimized)| {
+      Event::Minimize(Minimize {
+        window_address,
+        minimized,
+      })
+    }),
+    (any::<bool>(), any::<bool>()).prop_map(|(state, window)| {
+      Event::Screencast(Screencast {
+        state,
+        owner: match window {
+          true => ScreencastOwner::Window,
+          false => ScreencastOwner::Monitor,
+        },
+      })
+    }),
+    address().prop_map(|window_address| Event::WindowTitle(WindowTitle { window_address })),
+    (address(), last_field()).prop_map(|(window_address, window_title)| {
+      Event::WindowTitleV2(WindowTitleV2 {
+        window_address,
+        window_title,
+      })
+    }),
+    any::<bool>().prop_map(|state| Event::IgnoreGroupLock(IgnoreGroupLock { state })),
+    any::<bool>().prop_map(|state| Event::LockGroups(LockGroups { state })),
+    (any::<bool>(), prop::collection::vec(address(), 0..4))
+      .prop_map(|(state, addresses)| Event::ToggleGroup(ToggleGroup { state, addresses })),
+    address().prop_map(|address| Event::MoveIntoGroup(MoveIntoGroup { address })),
+    address().prop_map(|address| Event::MoveOutOfGroup(MoveOutOfGroup { address })),
+    Just(Event::ConfigReloaded),
+    (address(), any::<bool>()).prop_map(|(window_address, pin_state)| {
+      Event::Pin(Pin {
+        window_address,
+        pin_state,
+      })
+    }),
+    prop::option::of(address()).prop_map(|window_address| Event::Bell(Bell { window_address })),
+    last_field().prop_map(Event::Custom),
+    ("[a-z0-9]{1,16}", last_field())
+      .prop_filter("known event name", |(name, _)| EventKind::from_name(name)
+        .is_none())
+      .prop_map(|(name, data)| Event::Unknown { name, data }),
+  ]
+}
+
+proptest! {
+  #[test]
+  fn parse_after_to_wire_is_identity(event in event()) {
+    let line = event.to_wire();
+    let interpreter = Interpreter::new(&line);
+    prop_assert_eq!(interpreter.kind(), Some(event.kind()));
+    prop_assert_eq!(&interpreter.interpret().unwrap(), &event);
+    prop_assert_eq!(EventRef::from_line(&line).map(EventRef::to_owned), Some(event));
+  }
+}
+
+#[test]
+fn every_kind_is_covered() {
+  let mut runner = proptest::test_runner::TestRunner::deterministic();
+  let strategy = event();
+  let mut seen = EventKinds::NONE;
+  for _ in 0..10_000 {
+    seen |= strategy.new_tree(&mut runner).unwrap().current().kind();
+  }
+  assert_eq!(seen.iter().count(), EventKind::ALL.len());
+}
+
+#[test]
+fn special_characters() {
+  let open = Event::OpenWindow(OpenWindow {
+    window_address: WindowAddress(0x55d5a8b2c9a0),
+    workspace_name: "special:scratch".to_string(),
+    window_class: "org.gnome.Nautilus".to_string(),
+    window_title: "Files, Music >> Ölfarben — 🦀 (2)".to_string(),
+  });
+  assert_eq!(
+    open.to_wire(),
+    "openwindow>>55d5a8b2c9a0,special:scratch,org.gnome.Nautilus,Files, Music >> Ölfarben — 🦀 (2)"
+  );
+  assert_eq!(Interpreter::new(&open.to_wire()).interpret().unwrap(), open);
+
+  let moved = Event::MoveWorkspaceV2(MoveWorkspaceV2 {
+    workspace_id: 4,
+    workspace_name: "mail, chat".to_string(),
+    monitor_name: "DP-1".to_string(),
+  });
+  assert_eq!(moved.to_string(), "moveworkspacev2>>4,mail, chat,DP-1");
+  assert_eq!(Event::from("moveworkspacev2", "4,mail, chat,DP-1"), moved);
+}
+
+#[test]
+fn unknown_events_are_kept() {
+  let event = Event::from("futureevent", "a,b");
+  assert_eq!(
+    event,
+    Event::Unknown {
+      name: "futureevent".to_string(),
+      data: "a,b".to_string(),
+    }
+  );
+  assert_eq!(event.kind(), EventKind::Unknown);
+  assert_eq!(event.to_wire(), "futureevent>>a,b");
+}
@@ -0,0 +1,180 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{AddressList, ScreencastOwner, WindowAddress};
+
+/// Writes the data part of an event, after `>>`.
+pub(super) trait Wire {
+  fn write_wire(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+/// A single comma separated field of an event's data. Parsing borrows from the line, `Ref` is
+/// what the field looks like in the [`borrowed`](super::borrowed) structs.
+pub trait WireField: Sized {
+  type Ref<'a>;
+
+  fn parse_field(field: &str) -> Option<Self::Ref<'_>>;
+
+  fn to_owned_field(field: Self::Ref<'_>) -> Self;
+
+  fn write_field(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+/// Splits `data` into `N` fields without allocating. Only the field at `commas` may contain
+/// commas: the fields before it are split off from the left, the ones after it from the right.
+pub(super) fn split_fields<const N: usize>(data: &str, commas: usize) -> Option<[&str; N]> {
+  let mut fields = [""; N];
+  let mut rest = data;
+  for field in &mut fields[..commas] {
+    let (head, tail) = rest.split_once(',')?;
+    *field = head;
+    rest = tail;
+  }
+  for field in fields[commas + 1..].iter_mut().rev() {
+    let (head, tail) = rest.rsplit_once(',')?;
+    *field = tail;
+    rest = head;
+  }
+  fields[commas] = rest;
+  Some(fields)
+}
+
+impl WireField for String {
+  type Ref<'a> = &'a str;
+
+  fn parse_field(field: &str) -> Option<&str> {
+    Some(field)
+  }
+
+  fn to_owned_field(field: &str) -> Self {
+    field.to_string()
+  }
+
+  fn write_field(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self)
+  }
+}
+
+/// Fields that are plain values, parsed with `FromStr` and written with `Display`.
+macro_rules! value_fields {
+  ($($ty:ty),*) => {$(
+    impl WireField for $ty {
+      type Ref<'a> = $ty;
+
+      fn parse_field(field: &str) -> Option<Self> {
+        field.parse().ok()
+      }
+
+      fn to_owned_field(field: Self) -> Self {
+        field
+      }
+
+      fn write_field(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+      }
+    }
+  )*};
+}
+
+value_fields!(i32, u32, WindowAddress);
+
+/// `1` is `true`, anything else `false`.
+impl WireField for bool {
+  type Ref<'a> = bool;
+
+  fn parse_field(field: &str) -> Option<Self> {
+    Some(field == "1")
+  }
+
+  fn to_owned_field(field: Self) -> Self {
+    field
+  }
+
+  fn write_field(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", *self as u8)
+  }
+}
+
+impl WireField for ScreencastOwner {
+  type Ref<'a> = ScreencastOwner;
+
+  fn parse_field(field: &str) -> Option<Self> {
+    match field {
+      "0" => Some(ScreencastOwner::Monitor),
+      "1" => Some(ScreencastOwner::Window),
+      _ => None,
+    }
+  }
+
+  fn to_owned_field(field: Self) -> Self {
+    field
+  }
+
+  fn write_field(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ScreencastOwner::Monitor => f.write_str("0"),
+      ScreencastOwner::Window => f.write_str("1"),
+    }
+  }
+}
+
+/// An empty field is `None`.
+impl<T: WireField> WireField for Option<T> {
+  type Ref<'a> = Option<T::Ref<'a>>;
+
+  fn parse_field(field: &str) -> Option<Self::Ref<'_>> {
+    match field {
+      "" => Some(None),
+      field => T::parse_field(field).map(Some),
+    }
+  }
+
+  fn to_owned_field(field: Self::Ref<'_>) -> Self {
+    field.map(T::to_owned_field)
+  }
+
+  fn write_field(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Some(value) => value.write_field(f),
+      None => Ok(()),
+    }
+  }
+}
+
+/// A comma separated list, which has to be the field allowed to contain commas.
+impl WireField for Vec<WindowAddress> {
+  type Ref<'a> = AddressList<'a>;
+
+  fn parse_field(field: &str) -> Option<AddressList<'_>> {
+    AddressList::new(field)
+  }
+
+  fn to_owned_field(field: AddressList<'_>) -> Self {
+    field.iter().collect()
+  }
+
+  fn write_field(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, address) in self.iter().enumerate() {
+      if i > 0 {
+        f.write_str(",")?;
+      }
+      write!(f, "{}", address)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn split_around_the_field_with_commas() {
+    assert_eq!(split_fields("a", 0), Some(["a"]));
+    assert_eq!(split_fields("a,b,c", 1), Some(["a", "b,c"]));
+    assert_eq!(split_fields("a,b,c", 0), Some(["a,b", "c"]));
+    assert_eq!(split_fields("1,a,b,DP-1", 1), Some(["1", "a,b", "DP-1"]));
+    assert_eq!(split_fields::<2>("a", 1), None);
+  }
+}
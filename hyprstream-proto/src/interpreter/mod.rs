@@ -0,0 +1,48 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::events::{split_line, Event, EventKind};
+
+#[derive(Debug)]
+pub enum EventInterpretError {
+  InterpretationError(String),
+}
+
+impl fmt::Display for EventInterpretError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      EventInterpretError::InterpretationError(reason) => {
+        write!(f, "Failed to interpret event: {}", reason)
+      }
+    }
+  }
+}
+
+impl core::error::Error for EventInterpretError {}
+
+pub struct Interpreter<'a> {
+  pub raw: &'a str,
+}
+
+impl<'b> Interpreter<'b> {
+  pub fn new(raw: &'b str) -> Self {
+    Interpreter { raw }
+  }
+
+  /// Returns the kind of the event without parsing its data. `None` when the line is malformed.
+  pub fn kind(&self) -> Option<EventKind> {
+    let (event_type, _) = split_line(self.raw)?;
+    Some(EventKind::from_name(event_type).unwrap_or(EventKind::Unknown))
+  }
+
+  pub fn interpret(&self) -> Result<Event, EventInterpretError> {
+    let (event_type, data) = split_line(self.raw).ok_or_else(|| {
+      EventInterpretError::InterpretationError(format!("Invalid event format: {}", self.raw))
+    })?;
+
+    Event::parse(event_type, data).ok_or_else(|| {
+      EventInterpretError::InterpretationError(format!("Invalid {} data: {}", event_type, data))
+    })
+  }
+}
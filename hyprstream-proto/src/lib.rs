@@ -0,0 +1,19 @@
+//! Hyprland's IPC protocol without a runtime: the events read from the event socket, the
+//! commands and queries written to the request socket, and the types their replies decode into.
+//!
+//! Everything here is string parsing and formatting on top of `core` and `alloc`, so it works in
+//! bar widgets, WASM plugins or with any async runtime. `hyprlib` builds the tokio based
+//! `Listener` and `Dispatcher` on it and re-exports these modules.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod custom;
+pub mod dispatch;
+pub mod events;
+pub mod interpreter;
+pub mod monitor;
+pub mod query;
+
+pub use crate::dispatch::Dispatch;
@@ -0,0 +1,214 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// `MonitorDescription` splits the description Hyprland reports for a monitor into the parts that
+/// identify the physical display, so it can be recognised on any connector.
+///
+/// Descriptions are built from the EDID as `MAKE MODEL SERIAL`, optionally followed by
+/// ` (CONNECTOR)`. Make and model both contain spaces, so the make is matched against the vendor
+/// names EDID parsers commonly print, falling back to the first word. The last word is taken as
+/// the serial when it contains a digit and something is left for the model.
+///
+/// # Fields
+///
+/// * `make` - The manufacturer, e.g. `Dell Inc.`.
+/// * `model` - The model name, e.g. `DELL U2720Q`.
+/// * `serial` - The serial number, `None` when the EDID has none.
+/// * `connector` - The connector the description was reported for, e.g. `DP-2`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MonitorDescription {
+  pub make: String,
+  pub model: String,
+  pub serial: Option<String>,
+  pub connector: Option<String>,
+}
+
+/// Vendor names as printed from the EDID PNP ID table, longest match wins.
+const MAKES: &[&str] = &[
+  "Acer Technologies",
+  "Ancor Communications Inc",
+  "Apple Computer Inc",
+  "ASUSTek COMPUTER INC",
+  "AU Optronics",
+  "BenQ Corporation",
+  "BOE",
+  "Chimei Innolux Corporation",
+  "Dell Inc.",
+  "Eizo Nanao Corporation",
+  "Gigabyte Technology Co., Ltd.",
+  "Goldstar Company Ltd",
+  "Hewlett Packard",
+  "Iiyama North America",
+  "Lenovo Group Limited",
+  "LG Display",
+  "LG Electronics",
+  "Microstep",
+  "Philips Consumer Electronics Company",
+  "Samsung Display Corp",
+  "Samsung Electric Company",
+  "Sharp Corporation",
+  "Sony",
+  "ViewSonic Corporation",
+];
+
+impl MonitorDescription {
+  /// Parses a description. Never fails: anything that can't be split is kept in `make`.
+  pub fn parse(description: &str) -> Self {
+    let (rest, connector) = split_connector(description);
+    let has_serial_slot = !rest.ends_with(' ');
+    let rest = rest.trim();
+
+    let make = MAKES
+      .iter()
+      .filter(|make| {
+        rest
+          .strip_prefix(**make)
+          .is_some_and(|tail| tail.is_empty() || tail.starts_with(' '))
+      })
+      .max_by_key(|make| make.len())
+      .map(|make| make.to_string())
+      .unwrap_or_else(|| rest.split(' ').next().unwrap_or_default().to_string());
+    let rest = rest[make.len()..].trim();
+
+    let (model, serial) = match rest.rsplit_once(' ') {
+      Some((model, serial)) if has_serial_slot && serial.chars().any(|c| c.is_ascii_digit()) => {
+        (model.trim(), Some(serial.to_string()))
+      }
+      _ => (rest, None),
+    };
+
+    MonitorDescription {
+      make,
+      model: model.to_string(),
+      serial,
+      connector,
+    }
+  }
+
+  /// Builds a description from the separate `make`, `model` and `serial` fields of the `monitors`
+  /// query, which need no guessing.
+  pub fn from_parts(make: &str, model: &str, serial: &str, connector: Option<&str>) -> Self {
+    MonitorDescription {
+      make: make.to_string(),
+      model: model.to_string(),
+      serial: (!serial.is_empty()).then(|| serial.to_string()),
+      connector: connector.map(|connector| connector.to_string()),
+    }
+  }
+
+  /// Whether both describe the same physical display, regardless of the connector.
+  pub fn same_display(&self, other: &MonitorDescription) -> bool {
+    self.make == other.make && self.model == other.model && self.serial == other.serial
+  }
+}
+
+/// Splits a trailing ` (CONNECTOR)` off the description.
+fn split_connector(description: &str) -> (&str, Option<String>) {
+  if let Some(rest) = description.strip_suffix(')') {
+    if let Some((rest, connector)) = rest.rsplit_once(" (") {
+      if !connector.is_empty() && !connector.contains(' ') {
+        return (rest, Some(connector.to_string()));
+      }
+    }
+  }
+  (description, None)
+}
+
+/// Writes the description back in Hyprland's `MAKE MODEL SERIAL` form, without the connector.
+impl fmt::Display for MonitorDescription {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.make, self.model)?;
+    if let Some(serial) = &self.serial {
+      write!(f, " {}", serial)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parsed(description: &str) -> (String, String, Option<String>, Option<String>) {
+    let d = MonitorDescription::parse(description);
+    (d.make, d.model, d.serial, d.connector)
+  }
+
+  fn expect(
+    make: &str,
+    model: &str,
+    serial: Option<&str>,
+    connector: Option<&str>,
+  ) -> (String, String, Option<String>, Option<String>) {
+    (
+      make.to_string(),
+      model.to_string(),
+      serial.map(String::from),
+      connector.map(String::from),
+    )
+  }
+
+  #[test]
+  fn desktop_monitors() {
+    assert_eq!(
+      parsed("Dell Inc. DELL U2720Q 8XYZ123 (DP-2)"),
+      expect("Dell Inc.", "DELL U2720Q", Some("8XYZ123"), Some("DP-2"))
+    );
+    assert_eq!(
+      parsed("LG Electronics LG HDR 4K 0x00020F5B"),
+      expect("LG Electronics", "LG HDR 4K", Some("0x00020F5B"), None)
+    );
+    assert_eq!(
+      parsed("Samsung Electric Company C27G7xT HNAR400048 (HDMI-A-1)"),
+      expect(
+        "Samsung Electric Company",
+        "C27G7xT",
+        Some("HNAR400048"),
+        Some("HDMI-A-1")
+      )
+    );
+    assert_eq!(
+      parsed("Ancor Communications Inc ASUS VG248 G7LMQS012345"),
+      expect(
+        "Ancor Communications Inc",
+        "ASUS VG248",
+        Some("G7LMQS012345"),
+        None
+      )
+    );
+    assert_eq!(
+      parsed("Microstep MSI MAG274QRF-QD CC2H013900123"),
+      expect("Microstep", "MSI MAG274QRF-QD", Some("CC2H013900123"), None)
+    );
+  }
+
+  #[test]
+  fn laptop_panels_without_serial() {
+    assert_eq!(parsed("BOE 0x095F"), expect("BOE", "0x095F", None, None));
+    assert_eq!(
+      parsed("Chimei Innolux Corporation 0x1406 (eDP-1)"),
+      expect("Chimei Innolux Corporation", "0x1406", None, Some("eDP-1"))
+    );
+    assert_eq!(
+      parsed("Dell Inc. DELL U2720Q "),
+      expect("Dell Inc.", "DELL U2720Q", None, None)
+    );
+  }
+
+  #[test]
+  fn unknown_make_falls_back_to_first_word() {
+    assert_eq!(
+      parsed("Zowie XL2546K 9AL01234"),
+      expect("Zowie", "XL2546K", Some("9AL01234"), None)
+    );
+    assert_eq!(parsed(""), expect("", "", None, None));
+  }
+
+  #[test]
+  fn same_display_on_another_connector() {
+    let dp = MonitorDescription::parse("Dell Inc. DELL U2720Q 8XYZ123 (DP-2)");
+    let hdmi = MonitorDescription::parse("Dell Inc. DELL U2720Q 8XYZ123 (HDMI-A-1)");
+    assert!(dp.same_display(&hdmi));
+    assert_eq!(dp.to_string(), "Dell Inc. DELL U2720Q 8XYZ123");
+  }
+}
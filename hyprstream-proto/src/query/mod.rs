@@ -0,0 +1,379 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::{FullscreenMode, WindowAddress};
+use crate::monitor::MonitorDescription;
+
+/// The workspace a window or monitor refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct WorkspaceRef {
+  pub id: i32,
+  pub name: String,
+}
+
+/// A window, as returned by the `clients` and `activewindow` queries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", from = "ClientRepr")]
+pub struct Client {
+  pub address: WindowAddress,
+  #[serde(default)]
+  pub mapped: bool,
+  #[serde(default)]
+  pub hidden: bool,
+  #[serde(default)]
+  pub at: (i32, i32),
+  #[serde(default)]
+  pub size: (i32, i32),
+  #[serde(default)]
+  pub workspace: WorkspaceRef,
+  #[serde(default)]
+  pub floating: bool,
+  #[serde(default)]
+  pub monitor: i32,
+  #[serde(default)]
+  pub class: String,
+  #[serde(default)]
+  pub title: String,
+  #[serde(default)]
+  pub initial_class: String,
+  #[serde(default)]
+  pub initial_title: String,
+  #[serde(default)]
+  pub pid: i32,
+  #[serde(default)]
+  pub xwayland: bool,
+  #[serde(default)]
+  pub pinned: bool,
+  /// The mode Hyprland lays the window out with. Before Hyprland v0.42.0 `fullscreen` was a
+  /// boolean and `fullscreenMode` told maximized apart from fullscreen, both are read.
+  #[serde(default)]
+  pub fullscreen: FullscreenMode,
+  /// The mode the client was told about. Missing before Hyprland v0.42.0.
+  #[serde(default)]
+  pub fullscreen_client: FullscreenMode,
+  #[serde(default)]
+  pub grouped: Vec<WindowAddress>,
+  #[serde(default, rename = "focusHistoryID")]
+  pub focus_history_id: i32,
+}
+
+/// [`Client`] as decoded, with the `fullscreenMode` of Hyprland before v0.42.0 (0 fullscreen,
+/// 1 maximized) that only matters while decoding.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientRepr {
+  address: WindowAddress,
+  #[serde(default)]
+  mapped: bool,
+  #[serde(default)]
+  hidden: bool,
+  #[serde(default)]
+  at: (i32, i32),
+  #[serde(default)]
+  size: (i32, i32),
+  #[serde(default)]
+  workspace: WorkspaceRef,
+  #[serde(default)]
+  floating: bool,
+  #[serde(default)]
+  monitor: i32,
+  #[serde(default)]
+  class: String,
+  #[serde(default)]
+  title: String,
+  #[serde(default)]
+  initial_class: String,
+  #[serde(default)]
+  initial_title: String,
+  #[serde(default)]
+  pid: i32,
+  #[serde(default)]
+  xwayland: bool,
+  #[serde(default)]
+  pinned: bool,
+  #[serde(default)]
+  fullscreen: FullscreenMode,
+  #[serde(default)]
+  fullscreen_mode: Option<u8>,
+  #[serde(default)]
+  fullscreen_client: FullscreenMode,
+  #[serde(default)]
+  grouped: Vec<WindowAddress>,
+  #[serde(default, rename = "focusHistoryID")]
+  focus_history_id: i32,
+}
+
+impl From<ClientRepr> for Client {
+  fn from(repr: ClientRepr) -> Self {
+    let fullscreen = match (repr.fullscreen, repr.fullscreen_mode) {
+      (FullscreenMode::None, _) => FullscreenMode::None,
+      (_, Some(1)) => FullscreenMode::Maximized,
+      (mode, _) => mode,
+    };
+    Client {
+      address: repr.address,
+      mapped: repr.mapped,
+      hidden: repr.hidden,
+      at: repr.at,
+      size: repr.size,
+      workspace: repr.workspace,
+      floating: repr.floating,
+      monitor: repr.monitor,
+      class: repr.class,
+      title: repr.title,
+      initial_class: repr.initial_class,
+      initial_title: repr.initial_title,
+      pid: repr.pid,
+      xwayland: repr.xwayland,
+      pinned: repr.pinned,
+      fullscreen,
+      fullscreen_client: repr.fullscreen_client,
+      grouped: repr.grouped,
+      focus_history_id: repr.focus_history_id,
+    }
+  }
+}
+
+impl Client {
+  pub fn fullscreen_status(&self) -> FullscreenStatus {
+    FullscreenStatus {
+      address: self.address,
+      workspace_id: self.workspace.id,
+      mode: self.fullscreen,
+      client_mode: self.fullscreen_client,
+    }
+  }
+}
+
+/// A monitor, as returned by the `monitors` query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Monitor {
+  pub id: i32,
+  pub name: String,
+  #[serde(default)]
+  pub description: String,
+  #[serde(default)]
+  pub make: String,
+  #[serde(default)]
+  pub model: String,
+  #[serde(default)]
+  pub serial: String,
+  #[serde(default)]
+  pub width: u32,
+  #[serde(default)]
+  pub height: u32,
+  #[serde(default)]
+  pub refresh_rate: f64,
+  #[serde(default)]
+  pub x: i32,
+  #[serde(default)]
+  pub y: i32,
+  #[serde(default)]
+  pub active_workspace: WorkspaceRef,
+  #[serde(default)]
+  pub special_workspace: WorkspaceRef,
+  #[serde(default)]
+  pub scale: f64,
+  #[serde(default)]
+  pub focused: bool,
+  #[serde(default)]
+  pub dpms_status: bool,
+  #[serde(default)]
+  pub disabled: bool,
+}
+
+impl Monitor {
+  /// The display plugged into this monitor's connector. Uses the separate `make`, `model` and
+  /// `serial` fields when Hyprland reports them and parses `description` otherwise.
+  pub fn description(&self) -> MonitorDescription {
+    if self.make.is_empty() && self.model.is_empty() {
+      MonitorDescription {
+        connector: Some(self.name.clone()),
+        ..MonitorDescription::parse(&self.description)
+      }
+    } else {
+      MonitorDescription::from_parts(&self.make, &self.model, &self.serial, Some(&self.name))
+    }
+  }
+}
+
+/// A workspace, as returned by the `workspaces` and `activeworkspace` queries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+  pub id: i32,
+  pub name: String,
+  /// The name of the monitor the workspace is on.
+  #[serde(default)]
+  pub monitor: String,
+  #[serde(default, rename = "monitorID")]
+  pub monitor_id: i32,
+  #[serde(default)]
+  pub windows: u32,
+  #[serde(default, rename = "hasfullscreen")]
+  pub has_fullscreen: bool,
+  /// `None` when no window was focused on the workspace yet, which Hyprland sends as `0x0`.
+  #[serde(default, rename = "lastwindow", deserialize_with = "zero_is_none")]
+  pub last_window: Option<WindowAddress>,
+  #[serde(default, rename = "lastwindowtitle")]
+  pub last_window_title: String,
+}
+
+fn zero_is_none<'de, D>(deserializer: D) -> Result<Option<WindowAddress>, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  let address = Option::<WindowAddress>::deserialize(deserializer)?;
+  Ok(address.filter(|address| address.0 != 0))
+}
+
+/// The input devices, as returned by the `devices` query. Only keyboards are decoded.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Devices {
+  #[serde(default)]
+  pub keyboards: Vec<Keyboard>,
+}
+
+/// A keyboard in the reply of the `devices` query.
+///
+/// # Fields
+///
+/// * `name` - The name `activelayout` events use for the keyboard.
+/// * `layout` - The configured XKB layouts, comma separated.
+/// * `active_keymap` - The description of the active layout, as `activelayout` events carry it.
+/// * `main` - Whether this is the keyboard Hyprland treats as the main one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keyboard {
+  pub name: String,
+  #[serde(default)]
+  pub layout: String,
+  #[serde(default)]
+  pub active_keymap: String,
+  #[serde(default)]
+  pub main: bool,
+}
+
+/// Which window is fullscreen, and how.
+///
+/// # Fields
+///
+/// * `address` - The address of the window.
+/// * `workspace_id` - The ID of the workspace the window is on.
+/// * `mode` - The mode Hyprland lays the window out with.
+/// * `client_mode` - The mode the client was told about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FullscreenStatus {
+  pub address: WindowAddress,
+  pub workspace_id: i32,
+  pub mode: FullscreenMode,
+  pub client_mode: FullscreenMode,
+}
+
+impl FullscreenStatus {
+  /// The window covers its whole monitor, as opposed to being maximized.
+  pub fn is_fullscreen(&self) -> bool {
+    self.mode == FullscreenMode::Fullscreen
+  }
+
+  /// The client believes it is in a different mode than the one it is laid out with.
+  pub fn is_fake(&self) -> bool {
+    self.mode != self.client_mode
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CLIENT: &str = r#"{
+    "address": "0x55d5a8b2c9a0",
+    "mapped": true,
+    "hidden": false,
+    "at": [0, 0],
+    "size": [2560, 1440],
+    "workspace": { "id": 3, "name": "3" },
+    "floating": false,
+    "pseudo": false,
+    "monitor": 0,
+    "class": "mpv",
+    "title": "movie.mkv - mpv",
+    "initialClass": "mpv",
+    "initialTitle": "mpv",
+    "pid": 4242,
+    "xwayland": false,
+    "pinned": false,
+    "fullscreen": 1,
+    "fullscreenClient": 2,
+    "grouped": [],
+    "tags": [],
+    "swallowing": "0x0",
+    "focusHistoryID": 0
+  }"#;
+
+  #[test]
+  fn client_fullscreen_modes() {
+    let client: Client = serde_json::from_str(CLIENT).unwrap();
+    let status = client.fullscreen_status();
+    assert_eq!(status.address, WindowAddress(0x55d5a8b2c9a0));
+    assert_eq!(status.mode, FullscreenMode::Maximized);
+    assert_eq!(status.client_mode, FullscreenMode::Fullscreen);
+    assert!(!status.is_fullscreen());
+    assert!(status.is_fake());
+
+    let legacy = CLIENT
+      .replace(
+        r#""fullscreen": 1,"#,
+        r#""fullscreen": true, "fullscreenMode": 0,"#,
+      )
+      .replace(r#""fullscreenClient": 2,"#, "");
+    let client: Client = serde_json::from_str(&legacy).unwrap();
+    assert_eq!(client.fullscreen, FullscreenMode::Fullscreen);
+    assert_eq!(client.fullscreen_client, FullscreenMode::None);
+
+    let maximized = legacy.replace(r#""fullscreenMode": 0,"#, r#""fullscreenMode": 1,"#);
+    let client: Client = serde_json::from_str(&maximized).unwrap();
+    assert_eq!(client.fullscreen, FullscreenMode::Maximized);
+    let tiled = maximized.replace(r#""fullscreen": true,"#, r#""fullscreen": false,"#);
+    let client: Client = serde_json::from_str(&tiled).unwrap();
+    assert_eq!(client.fullscreen, FullscreenMode::None);
+  }
+  #[test]
+  fn workspace_without_windows() {
+    let workspace: Workspace = serde_json::from_str(
+      r#"{
+        "id": 2,
+        "name": "web",
+        "monitor": "DP-1",
+        "monitorID": 1,
+        "windows": 0,
+        "hasfullscreen": false,
+        "lastwindow": "0x0",
+        "lastwindowtitle": ""
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(workspace.monitor_id, 1);
+    assert_eq!(workspace.last_window, None);
+    let focused: Workspace =
+      serde_json::from_str(r#"{ "id": 1, "name": "1", "lastwindow": "0x1a" }"#).unwrap();
+    assert_eq!(focused.last_window, Some(WindowAddress(0x1a)));
+
+    let devices: Devices = serde_json::from_str(
+      r#"{
+        "mice": [],
+        "keyboards": [{
+          "address": "0x5621",
+          "name": "at-translated-set-2-keyboard",
+          "layout": "us,de",
+          "active_keymap": "German",
+          "main": true
+        }]
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(devices.keyboards[0].active_keymap, "German");
+  }
+}